@@ -6,7 +6,7 @@ use tf::prelude::*;
 
 fn main() {
     let root = &mut Scope::new();
-    let a = root.placeholder(DataType::Int32);
+    let a = root.placeholder(DataType::Int32).unwrap();
     let b = Constant::new(root, &[3, 3, 3, 3], &[2, 2]);
     // [[3, 3], [3, 3]]
     let add = ops::add(root, a, b, "").unwrap();
@@ -15,9 +15,7 @@ fn main() {
     // Feed a <- [[1, 2], [3, 4]]
     let feed_a = {
         let mut t = TypedTensor::<i32>::new(&[2, 2]);
-        for (i, x) in [1, 2, 3, 4].iter().enumerate() {
-            t[i] = *x;
-        }
+        t.clone_from_slice(&[1, 2, 3, 4]);
         t
     };
     session.feed(vec![(a, vec![TensorContent::Int32(feed_a)])]);
@@ -3,41 +3,65 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use super::{DataType, Graph, Session, SessionOptions, StepWithGraph};
+use super::{DataType, Graph, OperationData, Session, SessionOptions, Shape, StepWithGraph,
+            TypedTensor};
+use tf::OutputToken;
 use super::framework::*;
 use errors::*;
 
+mod saved_model;
+pub use self::saved_model::*;
+mod session_config;
+pub use self::session_config::*;
+
 /// A ClientSession object lets the caller drive the evaluation of the TensorFlow graph
 /// constructed with the Rust API.
 #[derive(Debug)]
 pub struct ClientSession<'g> {
     fetch: Vec<NodeIdent>,
     feed: Vec<(NodeIdent, Vec<TensorContent>)>,
+    targets: Vec<NodeIdent>,
     context: &'g mut Scope,
     reinit_vars: bool,
-    initialized: bool,
+    /// number of variable initializers which have already been run in the session
+    initialized: usize,
+    /// session in which the graph is run, created on the first run unless it was
+    /// provided along with the graph (e.g. when loading a SavedModel)
+    session: Option<Session>,
 }
 
 impl<'g> ClientSession<'g> {
     pub fn new(context: &'g mut Scope) -> Result<ClientSession<'g>> {
         if context.locked() {
             // can only create sessions out of root scopes
-            return Err(Error::from(ErrorKind::Stub));
+            return Err(Error::from(ErrorKind::LockedScope(context.name().to_owned())));
         }
         Ok(
             ClientSession {
                 fetch: vec![],
                 feed: vec![],
+                targets: vec![],
                 context,
                 reinit_vars: false,
-                initialized: false,
+                initialized: 0,
+                session: None,
             },
         )
     }
 
-    /// If variables had already been initialized, reinitialize them again.
+    /// Creates a client session which runs the graph in an existing session.
+    pub(crate) fn with_session(
+        context: &'g mut Scope,
+        session: Session,
+    ) -> Result<ClientSession<'g>> {
+        let mut client = ClientSession::new(context)?;
+        client.session = Some(session);
+        Ok(client)
+    }
+
+    /// If variables had already been initialized, reinitialize them again on the next run.
     ///
-    /// Default for this setting is false.
+    /// Default for this setting is false, and it's reset after the variables are initialized.
     pub fn reinitialize_vars(&mut self) -> &mut Self {
         self.reinit_vars = true;
         self
@@ -55,6 +79,18 @@ impl<'g> ClientSession<'g> {
         self
     }
 
+    /// Operations to run without fetching any output (e.g. a `Group` of updates).
+    pub fn target<I, T>(&mut self, targets: I) -> &mut Self
+    where
+        I: IntoIterator<Item = T>,
+        T: GetIdent,
+    {
+        for t in targets.into_iter() {
+            self.targets.push(t.get_ident());
+        }
+        self
+    }
+
     /// Input to feed to a graph node.
     pub fn feed<I, Id>(&mut self, inputs: I) -> &mut Self
     where
@@ -67,10 +103,51 @@ impl<'g> ClientSession<'g> {
         self
     }
 
-    /// Prune the session of previous feed/fetch inputs and results.
+    /// Input to feed to a graph node, taken from an `ndarray` array.
+    #[cfg(feature = "ndarray")]
+    pub fn feed_array<Id, T, D>(&mut self, id: Id, array: ::ndarray::ArrayView<T, D>) -> &mut Self
+    where
+        Id: Into<NodeIdent>,
+        T: ::tf::TensorType,
+        D: ::ndarray::Dimension,
+        TensorContent: From<TypedTensor<T>>,
+    {
+        self.feed.push((id.into(), vec![TensorContent::from_array(array)]));
+        self
+    }
+
+    /// Input to feed to the graph tensor with the given name.
+    ///
+    /// The name can be either a tensor name ("scope/op_name:0") or an operation name
+    /// ("scope/op_name"), which refers to its first output. Returns an error if there is
+    /// no such tensor in the graph.
+    pub fn feed_by_name<S>(&mut self, name: S, inputs: Vec<TensorContent>) -> Result<&mut Self>
+    where
+        S: AsRef<str>,
+    {
+        let name = name.as_ref();
+        let tensor = if name.contains(':') {
+            self.context.get_tensor_by_name(name)
+        } else {
+            self.context.get_tensor_by_name(&format!("{}:0", name))
+        };
+        match tensor {
+            Ok(tensor) => {
+                self.feed.push((tensor.into(), inputs));
+                Ok(self)
+            }
+            Err(err) => {
+                let reason = format!("{}", err);
+                Err(Error::from(ErrorKind::InvalidFeed(name.to_owned(), reason)))
+            }
+        }
+    }
+
+    /// Prune the session of previous feed/fetch inputs, targets and results.
     pub fn prune(&mut self) -> &mut Self {
         self.fetch.clear();
         self.feed.clear();
+        self.targets.clear();
         self
     }
 
@@ -86,59 +163,42 @@ impl<'g> ClientSession<'g> {
     }
 
     /// Run a TensorFlow session with the currently built-in graph in context.
-    /// All variables are initialized beforehand.
+    /// Variables which were not initialized yet are initialized beforehand.
+    ///
+    /// The underlying session is created on the first run and reused afterwards, so
+    /// variables keep their state between runs. The session options are only used
+    /// when the session is created and are ignored on later runs.
     ///
     /// Evaluates the tensors provided during the session construction to fetch.
     /// The number and order of outputs will match the construction phase.
     ///
     /// Returns error if any of the session input was inadequate.
     pub fn run(&mut self, options: Option<SessionOptions>) -> Result<Vec<TensorContent>> {
-        fn control_ops<'a, I>(
-            init_steep: &mut StepWithGraph,
-            steep: &mut StepWithGraph,
-            control_ops: I,
-        ) -> bool
-        where
-            I: Iterator<Item = &'a ControlOp>,
-        {
-            let mut any_init = false;
-            for init in control_ops {
-                if init.kind == ControlOpKind::VarInitializer {
-                    any_init = true;
-                    init_steep.add_target(&init.finished);
-                } else if init.kind == ControlOpKind::Ops {
-                    steep.add_target(&init.finished);
-                }
-            }
-            any_init
-        }
-
-        let graph = &*self.context.graph.borrow();
+        self.prepare(options)?;
         let registry = &*self.context.registry.borrow();
 
-        let mut session = if let Some(opts) = options {
-            Session::new(&opts, graph)
-        } else {
-            Session::new(&SessionOptions::new(), graph)
-        }?;
-
-        let root_deps = &self.context.scopes.borrow().control_dependencies;
-        let this_deps = self.context.own_scope.control_dependencies.iter();
-
-        let steep0 = &mut StepWithGraph::new();
         let steep1 = &mut StepWithGraph::new();
-
-        let any_init0 = control_ops(steep0, steep1, this_deps);
-        let any_init1 = control_ops(steep0, steep1, root_deps.iter());
-        // initialize variables
-        if (!self.initialized || self.reinit_vars) && (any_init0 || any_init1) {
-            session.run(steep0)?;
+        for target in self.control_targets() {
+            steep1.add_target(&target);
+        }
+        for target in &self.targets {
+            let ops = &*self.context.ops.borrow();
+            let op = ops.get(target)
+                .or_else(|| registry.get(target).map(|info| &info.data_origin.0))
+                .ok_or_else(|| {
+                    let reason = "not found in the graph".to_owned();
+                    ErrorKind::InvalidFetch(format!("{:?}", target), reason)
+                })?;
+            steep1.add_target(op);
         }
 
         // take output tokens
         let mut output_tokens = Vec::with_capacity(self.fetch.len());
         for output in &self.fetch {
-            let info = &registry[&output];
+            let info = registry.get(output).ok_or_else(|| {
+                ErrorKind::InvalidFetch(format!("{:?}", output), "not found in the graph".to_owned())
+            })?;
+            self.check_allowed(output, info, false)?;
             output_tokens.push(
                 (steep1.request_output(&info.data_origin.0, info.data_origin.1),
                  info.dtype),
@@ -146,40 +206,418 @@ impl<'g> ClientSession<'g> {
         }
         // feed input
         for &(ref token, ref inputs) in &self.feed {
-            let info = &registry[&token];
+            let info = registry.get(token).ok_or_else(|| {
+                ErrorKind::InvalidFeed(format!("{:?}", token), "not found in the graph".to_owned())
+            })?;
+            self.check_allowed(token, info, true)?;
             let op = &info.data_origin.0;
             let idx = info.data_origin.1;
             for input in inputs {
+                check_feed(info, input)?;
                 tensor_output_op!(input; StepWithGraph::add_input[steep1, op, idx,]);
             }
         }
 
-        session.run(steep1)?;
+        self.session.as_mut().unwrap().run(steep1)?;
         // fetch the outputs
         let mut results = Vec::with_capacity(self.fetch.len());
         for (token, dtype) in output_tokens {
-            let res = match dtype {
-                DataType::Bool => TensorContent::from(steep1.take_output::<bool>(token)?),
-                DataType::Double => TensorContent::from(steep1.take_output::<f64>(token)?),
-                DataType::Float => TensorContent::from(steep1.take_output::<f32>(token)?),
-                DataType::Int32 => TensorContent::from(steep1.take_output::<i32>(token)?),
-                DataType::UInt8 => TensorContent::from(steep1.take_output::<u8>(token)?),
-                DataType::Int16 => TensorContent::from(steep1.take_output::<i16>(token)?),
-                DataType::Int8 => TensorContent::from(steep1.take_output::<i8>(token)?),
-                DataType::Int64 => TensorContent::from(steep1.take_output::<i64>(token)?),
-                DataType::String => TensorContent::from(steep1.take_output::<String>(token)?), 
-                DataType::QUInt8 => TensorContent::from(steep1.take_output::<::QUInt8>(token)?), 
-                DataType::QUInt16 => TensorContent::from(steep1.take_output::<::QUInt16>(token)?), 
-                DataType::QInt16 => TensorContent::from(steep1.take_output::<::QInt16>(token)?), 
-                DataType::QInt32 => TensorContent::from(steep1.take_output::<::QInt32>(token)?), 
-                DataType::BFloat16 => TensorContent::from(steep1.take_output::<::BFloat16>(token)?), 
-                DataType::Complex64 => TensorContent::from(steep1.take_output::<::Complex32>(token)?,), 
-                DataType::Complex128 => TensorContent::from(steep1.take_output::<::Complex64>(token)?,), 
-                _ => return Err(Error::from(ErrorKind::Stub)),
-            };
-            results.push(res);
+            results.push(take_output(steep1, token, dtype)?);
         }
 
         Ok(results)
     }
+
+    /// Runs the graph with the current feeds fetching only `tensor`, and returns it as a
+    /// typed tensor. The fetches added to the session are kept for the next runs.
+    ///
+    /// Returns an error if the tensor is not of type `T`.
+    pub fn fetch_as<T, V>(&mut self, tensor: V) -> Result<TypedTensor<T>>
+    where
+        T: FromTensorContent,
+        V: Into<NodeIdent>,
+    {
+        let fetch = ::std::mem::replace(&mut self.fetch, vec![tensor.into()]);
+        let results = self.run(None);
+        self.fetch = fetch;
+        results?.pop().unwrap().try_into_typed()
+    }
+
+    /// Returns a callable which runs the graph feeding and fetching always the same
+    /// tensors.
+    ///
+    /// The tensors are looked up in the graph and validated once, so calling it only
+    /// requires the values to feed, in the same order as `feeds`. Outputs are returned
+    /// in the same order as `fetches`. If the session was not created yet, it will be
+    /// created with the default options on the first call.
+    ///
+    /// Returns an error if any of the tensors can't be found in the graph.
+    pub fn make_callable<'s, F, T, Fi, Ti>(
+        &'s mut self,
+        feeds: F,
+        fetches: T,
+    ) -> Result<Callable<'s, 'g>>
+    where
+        F: IntoIterator<Item = Fi>,
+        T: IntoIterator<Item = Ti>,
+        Fi: Into<NodeIdent>,
+        Ti: Into<NodeIdent>,
+    {
+        let mut feed_data = vec![];
+        for feed in feeds {
+            feed_data.push(self.tensor_data(feed.into(), true)?);
+        }
+        let mut fetch_data = vec![];
+        for fetch in fetches {
+            fetch_data.push(self.tensor_data(fetch.into(), false)?);
+        }
+        let targets = self.control_targets();
+        Ok(Callable {
+            client: self,
+            feeds: feed_data,
+            fetches: fetch_data,
+            targets,
+        })
+    }
+
+    /// Looks up the tensor to feed or fetch in the graph.
+    fn tensor_data(&self, ident: NodeIdent, feed: bool) -> Result<TensorData> {
+        let registry = &*self.context.registry.borrow();
+        match registry.get(&ident) {
+            Some(info) => {
+                self.check_allowed(&ident, info, feed)?;
+                Ok(info.clone())
+            }
+            None => {
+                let name = format!("{:?}", ident);
+                let reason = "not found in the graph".to_owned();
+                if feed {
+                    Err(Error::from(ErrorKind::InvalidFeed(name, reason)))
+                } else {
+                    Err(Error::from(ErrorKind::InvalidFetch(name, reason)))
+                }
+            }
+        }
+    }
+
+    /// Checks that the tensor was not marked as unfeedable (or unfetchable) in the graph.
+    fn check_allowed(&self, ident: &NodeIdent, info: &TensorData, feed: bool) -> Result<()> {
+        let name = || info.full_name.display().to_string();
+        if feed && !self.context.is_feedable(ident) {
+            let reason = "the tensor is marked as unfeedable (e.g. it's an input of a \
+                          `while_loop`)";
+            Err(Error::from(ErrorKind::InvalidFeed(name(), reason.to_owned())))
+        } else if !feed && !self.context.is_fetchable(ident) {
+            let reason = "the tensor is marked as unfetchable (e.g. it's only defined in \
+                          one of the branches of a `cond`)";
+            Err(Error::from(ErrorKind::InvalidFetch(name(), reason.to_owned())))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Creates the session if it doesn't exist yet and initializes the variables which
+    /// were not initialized yet.
+    fn prepare(&mut self, options: Option<SessionOptions>) -> Result<()> {
+        if self.session.is_none() {
+            let graph = &*self.context.graph.borrow();
+            let session = if let Some(opts) = options {
+                Session::new(&opts, graph)
+            } else {
+                Session::new(&SessionOptions::new(), graph)
+            }?;
+            self.session = Some(session);
+        }
+        let session = self.session.as_mut().unwrap();
+
+        // initialize variables, in creation order as initializers may depend on
+        // previously created variables
+        let var_inits = &self.context.scopes.borrow().var_initializers;
+        if self.reinit_vars {
+            self.initialized = 0;
+        }
+        for init in &var_inits[self.initialized..] {
+            let init_step = &mut StepWithGraph::new();
+            init_step.add_target(&init.finished);
+            session.run(init_step)?;
+            self.initialized += 1;
+        }
+        self.reinit_vars = false;
+        Ok(())
+    }
+
+    /// Control dependencies which must be run along with the graph.
+    fn control_targets(&self) -> Vec<OperationData> {
+        let root_deps = &self.context.scopes.borrow().control_dependencies;
+        let this_deps = self.context.own_scope.control_dependencies.iter();
+        this_deps
+            .chain(root_deps.iter())
+            .filter(|ctrl| ctrl.kind == ControlOpKind::Ops)
+            .map(|ctrl| ctrl.finished.clone())
+            .collect()
+    }
+}
+
+/// A prepared run of a client session with a fixed set of feeds and fetches,
+/// see `ClientSession::make_callable`.
+#[derive(Debug)]
+pub struct Callable<'s, 'g: 's> {
+    client: &'s mut ClientSession<'g>,
+    feeds: Vec<TensorData>,
+    fetches: Vec<TensorData>,
+    targets: Vec<OperationData>,
+}
+
+impl<'s, 'g> Callable<'s, 'g> {
+    /// Runs the graph feeding the given values, one per feed of the callable and in
+    /// the same order, and returns the fetched outputs.
+    pub fn call(&mut self, inputs: &[TensorContent]) -> Result<Vec<TensorContent>> {
+        if inputs.len() != self.feeds.len() {
+            return Err(Error::from(ErrorKind::InvalidArgument(format!(
+                "expected {} inputs, found {}",
+                self.feeds.len(),
+                inputs.len()
+            ))));
+        }
+        self.client.prepare(None)?;
+
+        let step = &mut StepWithGraph::new();
+        for target in &self.targets {
+            step.add_target(target);
+        }
+        let mut output_tokens = Vec::with_capacity(self.fetches.len());
+        for info in &self.fetches {
+            let &(ref op, idx) = &info.data_origin;
+            output_tokens.push((step.request_output(op, idx), info.dtype));
+        }
+        for (info, input) in self.feeds.iter().zip(inputs) {
+            check_feed(info, input)?;
+            let &(ref op, idx) = &info.data_origin;
+            tensor_output_op!(input; StepWithGraph::add_input[step, op, idx,]);
+        }
+
+        self.client.session.as_mut().unwrap().run(step)?;
+        let mut results = Vec::with_capacity(self.fetches.len());
+        for (token, dtype) in output_tokens {
+            results.push(take_output(step, token, dtype)?);
+        }
+        Ok(results)
+    }
+}
+
+/// Takes the requested output from a finished step.
+fn take_output(
+    step: &mut StepWithGraph,
+    token: OutputToken,
+    dtype: DataType,
+) -> Result<TensorContent> {
+    Ok(match dtype {
+        DataType::Bool => TensorContent::from(step.take_output::<bool>(token)?),
+        DataType::Double => TensorContent::from(step.take_output::<f64>(token)?),
+        DataType::Float => TensorContent::from(step.take_output::<f32>(token)?),
+        DataType::Int32 => TensorContent::from(step.take_output::<i32>(token)?),
+        DataType::UInt8 => TensorContent::from(step.take_output::<u8>(token)?),
+        DataType::Int16 => TensorContent::from(step.take_output::<i16>(token)?),
+        DataType::Int8 => TensorContent::from(step.take_output::<i8>(token)?),
+        DataType::Int64 => TensorContent::from(step.take_output::<i64>(token)?),
+        DataType::String => TensorContent::from(step.take_output::<String>(token)?),
+        DataType::QUInt8 => TensorContent::from(step.take_output::<::QUInt8>(token)?),
+        DataType::QUInt16 => TensorContent::from(step.take_output::<::QUInt16>(token)?),
+        DataType::QInt16 => TensorContent::from(step.take_output::<::QInt16>(token)?),
+        DataType::QInt32 => TensorContent::from(step.take_output::<::QInt32>(token)?),
+        DataType::BFloat16 => TensorContent::from(step.take_output::<::BFloat16>(token)?),
+        DataType::Complex64 => TensorContent::from(step.take_output::<::Complex32>(token)?),
+        DataType::Complex128 => TensorContent::from(step.take_output::<::Complex64>(token)?),
+        _ => return Err(Error::from(ErrorKind::UnknownDType(dtype))),
+    })
+}
+
+/// Checks that the fed tensor matches the type and the known dimensions of the
+/// graph tensor.
+fn check_feed(info: &TensorData, input: &TensorContent) -> Result<()> {
+    let name = || format!("feed of `{}`", info.full_name.display());
+    if input.get_datatype() != info.dtype {
+        return Err(Error::from(ErrorKind::DTypeMismatch(
+            name(),
+            vec![info.dtype],
+            input.get_datatype(),
+        )));
+    }
+    if let Some(rank) = info.shape.dims() {
+        let dims = input.get_dims();
+        let matches = dims.len() == rank
+            && dims.iter()
+                .enumerate()
+                .all(|(i, d)| info.shape[i].map(|x| x == *d as i64).unwrap_or(true));
+        if !matches {
+            let found = Shape::from(Some(dims.iter().map(|d| Some(*d as i64)).collect()));
+            return Err(Error::from(ErrorKind::ShapeMismatch(
+                name(),
+                info.shape.clone(),
+                found,
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg(test)]
+fn test_feed_by_name() {
+    let mut context = Scope::new();
+    let x = {
+        let scope = &mut context.name_scope("input", None);
+        scope.placeholder_with_name(DataType::Int32, "x").unwrap()
+    };
+    let y = ::ops::math_ops::add(&mut context, x, x, "").unwrap();
+
+    let mut session = ClientSession::new(&mut context).unwrap();
+    let mut input = TypedTensor::<i32>::new(&[1]);
+    input[0] = 2;
+    session.feed_by_name("input_0/x", vec![TensorContent::from(input)]).unwrap().fetch(vec![y]);
+    let results = session.run(None).unwrap();
+    match results[0] {
+        TensorContent::Int32(ref val) => assert_eq!(val[0], 4),
+        _ => panic!("wrong type"),
+    }
+    assert!(session.feed_by_name("input_0/z", vec![]).is_err());
+}
+
+#[test]
+#[cfg(test)]
+fn test_feed_validation() {
+    let mut context = Scope::new();
+    let shape = Shape::from(Some(vec![None, Some(2)]));
+    let x = context.placeholder_with_shape(DataType::Float, shape, "x").unwrap();
+    assert_eq!(x.get_shape(&context).dims(), Some(2));
+    let y = ::ops::math_ops::add(&mut context, x, x, "").unwrap();
+
+    let mut session = ClientSession::new(&mut context).unwrap();
+    let input = TensorContent::from(TypedTensor::<f32>::new(&[3, 2]));
+    session.feed(vec![(x, vec![input])]).fetch(vec![y]);
+    assert!(session.run(None).is_ok());
+
+    let input = TensorContent::from(TypedTensor::<f32>::new(&[3, 3]));
+    session.prune().feed(vec![(x, vec![input])]).fetch(vec![y]);
+    match *session.run(None).unwrap_err().kind() {
+        ErrorKind::ShapeMismatch(..) => {}
+        ref err => panic!("unexpected error: {}", err),
+    }
+
+    let input = TensorContent::from(TypedTensor::<i32>::new(&[3, 2]));
+    session.prune().feed(vec![(x, vec![input])]).fetch(vec![y]);
+    match *session.run(None).unwrap_err().kind() {
+        ErrorKind::DTypeMismatch(..) => {}
+        ref err => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn test_persistent_session() {
+    let mut context = Scope::new();
+    let var = Variable::new(&mut context, &[1_i32], &[] as &[i32]);
+    let one = Constant::new(&mut context, &[1_i32], &[] as &[i32]);
+    let op = ::ops::state_ops::assign_add(&mut context, var, one, false, "").unwrap();
+
+    let mut session = ClientSession::new(&mut context).unwrap();
+    session.fetch(vec![op]);
+    fn step(session: &mut ClientSession) -> i32 {
+        match session.run(None).unwrap()[0] {
+            TensorContent::Int32(ref val) => val[0],
+            _ => panic!("wrong type"),
+        }
+    }
+    // the variable keeps its state between runs
+    assert_eq!(step(&mut session), 2);
+    assert_eq!(step(&mut session), 3);
+    session.reinitialize_vars();
+    assert_eq!(step(&mut session), 2);
+}
+
+#[test]
+#[cfg(test)]
+fn test_make_callable() {
+    let mut context = Scope::new();
+    let x = context.placeholder(DataType::Int32).unwrap();
+    let y = ::ops::math_ops::add(&mut context, x, x, "").unwrap();
+
+    let mut session = ClientSession::new(&mut context).unwrap();
+    let mut callable = session.make_callable(vec![x], vec![y]).unwrap();
+    for i in 0..3 {
+        let mut input = TypedTensor::<i32>::new(&[1]);
+        input[0] = i;
+        let results = callable.call(&[TensorContent::from(input)]).unwrap();
+        match results[0] {
+            TensorContent::Int32(ref val) => assert_eq!(val[0], i * 2),
+            _ => panic!("wrong type"),
+        }
+    }
+    assert!(callable.call(&[]).is_err());
+}
+
+#[test]
+#[cfg(test)]
+fn test_run_target() {
+    use ops::control_flow_ops::Group;
+
+    let mut context = Scope::new();
+    let var = Variable::new(&mut context, &[1_i32], &[] as &[i32]);
+    let one = Constant::new(&mut context, &[1_i32], &[] as &[i32]);
+    let op = ::ops::state_ops::assign_add(&mut context, var, one, false, "").unwrap();
+    let update = Group::new(&mut context, &[op], "update").unwrap();
+
+    let mut session = ClientSession::new(&mut context).unwrap();
+    session.target(vec![update]);
+    assert!(session.run(None).unwrap().is_empty());
+    assert!(session.run(None).unwrap().is_empty());
+    session.prune().fetch(vec![var]);
+    match session.run(None).unwrap()[0] {
+        TensorContent::Int32(ref val) => assert_eq!(val[0], 3),
+        _ => panic!("wrong type"),
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn test_unfetchable_and_unfeedable() {
+    let mut context = Scope::new();
+    let x = context.placeholder(DataType::Int32).unwrap();
+    let y = ::ops::math_ops::add(&mut context, x, x, "").unwrap();
+    context.prevent_feeding(x);
+    context.prevent_fetching(y);
+
+    let mut session = ClientSession::new(&mut context).unwrap();
+    session.fetch(vec![y]);
+    match *session.run(None).unwrap_err().kind() {
+        ErrorKind::InvalidFetch(..) => {}
+        ref err => panic!("unexpected error: {}", err),
+    }
+    let input = TensorContent::from(TypedTensor::<i32>::new(&[1]));
+    session.prune().feed(vec![(x, vec![input])]);
+    match *session.run(None).unwrap_err().kind() {
+        ErrorKind::InvalidFeed(..) => {}
+        ref err => panic!("unexpected error: {}", err),
+    }
+    assert!(session.make_callable(vec![x], Vec::<NodeIdent>::new()).is_err());
+}
+
+#[test]
+#[cfg(test)]
+fn test_fetch_as() {
+    let mut context = Scope::new();
+    let x = Constant::new(&mut context, &[1_i32, 2], &[2]);
+    let y = ::ops::math_ops::add(&mut context, x, x, "").unwrap();
+
+    let mut session = ClientSession::new(&mut context).unwrap();
+    let y_val = session.fetch_as::<i32, _>(y).unwrap();
+    assert_eq!(&y_val[..], &[2, 4]);
+    match *session.fetch_as::<f32, _>(y).unwrap_err().kind() {
+        ErrorKind::DTypeMismatch(..) => {}
+        ref err => panic!("unexpected error: {}", err),
+    }
+
+    let content = TensorContent::from(y_val);
+    assert!(content.clone().try_into_typed::<i64>().is_err());
+    assert_eq!(content.try_into_typed::<i32>().unwrap().dims(), &[2]);
 }
@@ -0,0 +1,313 @@
+//! Loading of SavedModel bundles.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use super::*;
+use framework::protobuf::{read_map_entry, ProtoReader};
+
+/// Name of the SavedModel protocol buffer file inside an export directory.
+const SAVED_MODEL_FILENAME_PB: &str = "saved_model.pb";
+
+/// Default signature key used by TensorFlow when exporting a model.
+pub const DEFAULT_SERVING_SIGNATURE_DEF_KEY: &str = "serving_default";
+
+/// Tag of the MetaGraph used for serving.
+pub const SERVING_TAG: &str = "serve";
+
+/// Inputs and outputs of a computation exported in a SavedModel.
+#[derive(Debug, Clone)]
+pub struct SignatureDef {
+    /// The method name, e.g. "tensorflow/serving/predict".
+    pub method_name: String,
+    /// Input tensors, by the logical name given when exporting the model.
+    pub inputs: HashMap<String, Tensor>,
+    /// Output tensors, by the logical name given when exporting the model.
+    pub outputs: HashMap<String, Tensor>,
+}
+
+/// A model loaded from a SavedModel directory.
+///
+/// The graph is loaded into a new `Scope`, and the variables are restored in a session
+/// which is handed to the `ClientSession` returned by `client_session`:
+///
+/// ```ignore
+/// let mut bundle = SavedModelBundle::load("/path/to/export", &[SERVING_TAG], None)?;
+/// let (x, y) = {
+///     let signature = bundle.signature(DEFAULT_SERVING_SIGNATURE_DEF_KEY)?;
+///     (signature.inputs["x"], signature.outputs["y"])
+/// };
+/// let mut session = bundle.client_session()?;
+/// session.feed(vec![(x, vec![input])]).fetch(vec![y]);
+/// let results = session.run(None)?;
+/// ```
+#[derive(Debug)]
+pub struct SavedModelBundle {
+    scope: Scope,
+    session: Option<Session>,
+    signatures: HashMap<String, SignatureDef>,
+}
+
+impl SavedModelBundle {
+    /// Loads the MetaGraph tagged with `tags` from the SavedModel in `export_dir`.
+    pub fn load<P, S>(
+        export_dir: P,
+        tags: &[S],
+        options: Option<SessionOptions>,
+    ) -> Result<SavedModelBundle>
+    where
+        P: AsRef<Path>,
+        S: AsRef<str>,
+    {
+        let export_dir = export_dir.as_ref();
+        let mut scope = Scope::new();
+        let session = {
+            let graph = &mut *scope.graph.borrow_mut();
+            let options = options.unwrap_or_else(SessionOptions::new);
+            let tags = tags.iter().map(|t| t.as_ref());
+            Session::from_saved_model(&options, tags, graph, export_dir)?
+        };
+        let tensors = scope.register_graph_ops(&HashSet::new())?;
+
+        let mut saved_model = vec![];
+        File::open(export_dir.join(SAVED_MODEL_FILENAME_PB))?.read_to_end(&mut saved_model)?;
+        let tags: HashSet<&str> = tags.iter().map(|t| t.as_ref()).collect();
+        let signatures = read_signatures(&saved_model, &tags, &tensors)?;
+
+        Ok(SavedModelBundle {
+            scope,
+            session: Some(session),
+            signatures,
+        })
+    }
+
+    /// Returns the signature with the given key.
+    pub fn signature(&self, key: &str) -> Result<&SignatureDef> {
+        self.signatures
+            .get(key)
+            .ok_or_else(|| Error::from(ErrorKind::NotFound(format!("signature `{}`", key))))
+    }
+
+    /// Returns all the signatures of the loaded MetaGraph, by key.
+    pub fn signatures(&self) -> &HashMap<String, SignatureDef> {
+        &self.signatures
+    }
+
+    /// Returns the scope holding the loaded graph.
+    pub fn scope(&mut self) -> &mut Scope {
+        &mut self.scope
+    }
+
+    /// Returns a client session which runs the graph in the session where the
+    /// model variables were restored.
+    ///
+    /// The restored session is handed over to the returned `ClientSession`, so this
+    /// can only be called once.
+    pub fn client_session(&mut self) -> Result<ClientSession> {
+        let session = self.session
+            .take()
+            .ok_or_else(|| {
+                let msg = "the session of this model was already taken".to_owned();
+                Error::from(ErrorKind::InvalidArgument(msg))
+            })?;
+        ClientSession::with_session(&mut self.scope, session)
+    }
+}
+
+/// Reads the signatures of the MetaGraph tagged with `tags` from a serialized
+/// `SavedModel` protocol buffer.
+fn read_signatures(
+    saved_model: &[u8],
+    tags: &HashSet<&str>,
+    tensors: &HashMap<String, Tensor>,
+) -> Result<HashMap<String, SignatureDef>> {
+    // SavedModel { repeated MetaGraphDef meta_graphs = 2; }
+    let mut reader = ProtoReader::new(saved_model);
+    while let Some((field, value)) = reader.next_field()? {
+        if field != 2 {
+            continue;
+        }
+        // MetaGraphDef { MetaInfoDef meta_info_def = 1; map<string, SignatureDef> signature_def = 5; }
+        let mut meta_graph = ProtoReader::new(value.as_bytes()?);
+        let mut meta_graph_tags = HashSet::new();
+        let mut signatures = vec![];
+        while let Some((field, value)) = meta_graph.next_field()? {
+            match field {
+                1 => {
+                    // MetaInfoDef { repeated string tags = 4; }
+                    let mut meta_info = ProtoReader::new(value.as_bytes()?);
+                    while let Some((field, value)) = meta_info.next_field()? {
+                        if field == 4 {
+                            meta_graph_tags.insert(value.as_str()?);
+                        }
+                    }
+                }
+                5 => signatures.push(read_map_entry(value.as_bytes()?)?),
+                _ => {}
+            }
+        }
+        // only the tensors of the loaded MetaGraph were registered
+        if &meta_graph_tags == tags {
+            let mut resolved = HashMap::new();
+            for (key, signature) in signatures {
+                resolved.insert(key.to_owned(), read_signature(signature, tensors)?);
+            }
+            return Ok(resolved);
+        }
+    }
+    Err(Error::from(ErrorKind::NotFound(format!("MetaGraph with tags {:?}", tags))))
+}
+
+/// Reads a serialized `SignatureDef` protocol buffer.
+fn read_signature(signature: &[u8], tensors: &HashMap<String, Tensor>) -> Result<SignatureDef> {
+    // SignatureDef {
+    //     map<string, TensorInfo> inputs = 1;
+    //     map<string, TensorInfo> outputs = 2;
+    //     string method_name = 3;
+    // }
+    // TensorInfo { string name = 1; ... }
+    let tensor_info = |info: &[u8]| -> Result<Tensor> {
+        let mut reader = ProtoReader::new(info);
+        while let Some((field, value)) = reader.next_field()? {
+            if field == 1 {
+                let name = value.as_str()?;
+                return tensors.get(name).cloned().ok_or_else(|| {
+                    Error::from(ErrorKind::NotFound(name.to_owned()))
+                });
+            }
+        }
+        Err(Error::from(ErrorKind::InvalidArgument(
+            "only tensors given by name are supported in signatures".to_owned(),
+        )))
+    };
+    let mut reader = ProtoReader::new(signature);
+    let mut signature = SignatureDef {
+        method_name: String::new(),
+        inputs: HashMap::new(),
+        outputs: HashMap::new(),
+    };
+    while let Some((field, value)) = reader.next_field()? {
+        match field {
+            1 => {
+                let (key, info) = read_map_entry(value.as_bytes()?)?;
+                signature.inputs.insert(key.to_owned(), tensor_info(info)?);
+            }
+            2 => {
+                let (key, info) = read_map_entry(value.as_bytes()?)?;
+                signature.outputs.insert(key.to_owned(), tensor_info(info)?);
+            }
+            3 => signature.method_name = value.as_str()?.to_owned(),
+            _ => {}
+        }
+    }
+    Ok(signature)
+}
+
+#[test]
+#[cfg(test)]
+fn test_load_saved_model() {
+    use TypedTensor;
+
+    let export_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/half_plus_two");
+    let mut bundle = SavedModelBundle::load(&export_dir, &[SERVING_TAG], None).unwrap();
+    let (x, y) = {
+        let signature = bundle.signature(DEFAULT_SERVING_SIGNATURE_DEF_KEY).unwrap();
+        assert_eq!(signature.method_name, "tensorflow/serving/predict");
+        (signature.inputs["x"], signature.outputs["y"])
+    };
+    let mut input = TypedTensor::<f32>::new(&[2]);
+    input[0] = 2.0;
+    input[1] = 4.0;
+    let mut session = bundle.client_session().unwrap();
+    session.feed(vec![(x, vec![TensorContent::from(input)])]).fetch(vec![y]);
+    let results = session.run(None).unwrap();
+    test_suite!(results; assert: {[0;Float] == [3_f32, 4.]});
+}
+
+#[test]
+#[cfg(test)]
+fn test_load_saved_model_variables() {
+    use std::env;
+    use std::fs;
+    use framework::protobuf::ProtoWriter;
+    use ops::{math_ops, state_ops};
+    use train::Saver;
+    use TypedTensor;
+
+    fn map_entry(key: &str, value: &[u8]) -> Vec<u8> {
+        let mut entry = ProtoWriter::new();
+        entry.string(1, key).bytes(2, value);
+        entry.into_bytes()
+    }
+    fn tensor_info(name: &str) -> Vec<u8> {
+        let mut info = ProtoWriter::new();
+        info.string(1, name);
+        info.into_bytes()
+    }
+
+    // exports a model computing `y = w * x`, where `w` is initialized to 1 but saved as 3,
+    // the checkpoint is written by the `Saver` so it's generated here instead of checked in
+    let export_dir = env::temp_dir().join(format!("tf_rs_saved_model_{}", ::std::process::id()));
+    {
+        let mut root = Scope::new();
+        let x = root.placeholder(DataType::Float).unwrap();
+        let init = root.constant(&[1_f32], &[] as &[i32], "").unwrap();
+        let w = root.get_variable_with_initializer(init, true, "w").unwrap();
+        let y = math_ops::multiply(&mut root, w, x, "y").unwrap();
+        let change = {
+            let value = root.constant(&[3_f32], &[] as &[i32], "").unwrap();
+            state_ops::assign(&mut root, w, value, false, "").unwrap()
+        };
+        let mut saver = Saver::new(&mut root, "").unwrap();
+
+        // SavedModel {
+        //     int64 saved_model_schema_version = 1;
+        //     repeated MetaGraphDef meta_graphs = 2;
+        // }
+        // MetaGraphDef {
+        //     MetaInfoDef meta_info_def = 1;
+        //     GraphDef graph_def = 2;
+        //     SaverDef saver_def = 3;
+        //     map<string, SignatureDef> signature_def = 5;
+        // }
+        let mut signature = ProtoWriter::new();
+        signature
+            .bytes(1, &map_entry("x", &tensor_info(&format!("{}:0", x.get_name(&root)))))
+            .bytes(2, &map_entry("y", &tensor_info(&format!("{}:0", y.get_name(&root)))))
+            .string(3, "tensorflow/serving/predict");
+        let mut meta_info = ProtoWriter::new();
+        meta_info.string(4, SERVING_TAG);
+        let mut meta_graph = ProtoWriter::new();
+        meta_graph
+            .bytes(1, &meta_info.into_bytes())
+            .bytes(2, &root.graph.borrow().graph_def().unwrap())
+            .bytes(3, &saver.saver_def(&root))
+            .bytes(5, &map_entry(DEFAULT_SERVING_SIGNATURE_DEF_KEY, &signature.into_bytes()));
+        let mut saved_model = ProtoWriter::new();
+        saved_model.int64(1, 1).bytes(2, &meta_graph.into_bytes());
+
+        let mut session = ClientSession::new(&mut root).unwrap();
+        session.target(vec![change]);
+        session.run(None).unwrap();
+        saver.save(&mut session, export_dir.join("variables/variables"), None).unwrap();
+        fs::write(export_dir.join(SAVED_MODEL_FILENAME_PB), saved_model.into_bytes()).unwrap();
+    }
+
+    let mut bundle = SavedModelBundle::load(&export_dir, &[SERVING_TAG], None).unwrap();
+    let (x, y) = {
+        let signature = bundle.signature(DEFAULT_SERVING_SIGNATURE_DEF_KEY).unwrap();
+        (signature.inputs["x"], signature.outputs["y"])
+    };
+    let mut input = TypedTensor::<f32>::new(&[2]);
+    input[0] = 2.0;
+    input[1] = 4.0;
+    {
+        let mut session = bundle.client_session().unwrap();
+        session.feed(vec![(x, vec![TensorContent::from(input)])]).fetch(vec![y]);
+        let results = session.run(None).unwrap();
+        test_suite!(results; assert: {[0;Float] == [6_f32, 12.]});
+    }
+    fs::remove_dir_all(&export_dir).unwrap();
+}
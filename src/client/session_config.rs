@@ -0,0 +1,257 @@
+//! Typed session configuration.
+
+use super::SessionOptions;
+use errors::*;
+use framework::protobuf::ProtoWriter;
+
+/// Optimization level of the graph optimizer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptLevel {
+    /// Common subexpression elimination and constant folding.
+    L1,
+    /// No optimizations.
+    L0,
+}
+
+/// Level of the XLA just-in-time compilation for the whole session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlobalJitLevel {
+    Default,
+    Off,
+    On1,
+    On2,
+}
+
+/// Configuration of a session, serialized as a `ConfigProto` to set up the
+/// `SessionOptions` of a `ClientSession`:
+///
+/// ```ignore
+/// let options = SessionConfig::new()
+///     .intra_op_parallelism_threads(4)
+///     .inter_op_parallelism_threads(2)
+///     .allow_soft_placement(true)
+///     .session_options()?;
+/// let results = session.run(Some(options))?;
+/// ```
+///
+/// Settings which are not set are left to the TensorFlow defaults.
+#[derive(Debug, Clone, Default)]
+pub struct SessionConfig {
+    device_count: Vec<(String, i32)>,
+    intra_op_parallelism_threads: Option<i32>,
+    inter_op_parallelism_threads: Option<i32>,
+    use_per_session_threads: Option<bool>,
+    allow_soft_placement: Option<bool>,
+    log_device_placement: Option<bool>,
+    operation_timeout_in_ms: Option<i64>,
+    gpu_memory_fraction: Option<f64>,
+    gpu_allow_growth: Option<bool>,
+    common_subexpression_elimination: Option<bool>,
+    constant_folding: Option<bool>,
+    function_inlining: Option<bool>,
+    opt_level: Option<OptLevel>,
+    global_jit_level: Option<GlobalJitLevel>,
+}
+
+impl SessionConfig {
+    pub fn new() -> SessionConfig {
+        SessionConfig::default()
+    }
+
+    /// Maximum number of devices of the given type (e.g. "CPU" or "GPU") to use.
+    pub fn device_count<S: AsRef<str>>(&mut self, device_type: S, count: i32) -> &mut Self {
+        self.device_count.push((device_type.as_ref().to_owned(), count));
+        self
+    }
+
+    /// Number of threads used to parallelize the execution of individual ops.
+    /// If 0, the system picks an appropriate number.
+    pub fn intra_op_parallelism_threads(&mut self, threads: i32) -> &mut Self {
+        self.intra_op_parallelism_threads = Some(threads);
+        self
+    }
+
+    /// Number of threads used to run independent ops in parallel.
+    /// If 0, the system picks an appropriate number.
+    pub fn inter_op_parallelism_threads(&mut self, threads: i32) -> &mut Self {
+        self.inter_op_parallelism_threads = Some(threads);
+        self
+    }
+
+    /// Use a thread pool owned by the session instead of the global one.
+    pub fn use_per_session_threads(&mut self, val: bool) -> &mut Self {
+        self.use_per_session_threads = Some(val);
+        self
+    }
+
+    /// Place ops on the CPU if they can't be placed on the requested device.
+    pub fn allow_soft_placement(&mut self, val: bool) -> &mut Self {
+        self.allow_soft_placement = Some(val);
+        self
+    }
+
+    /// Log the device each op is placed on.
+    pub fn log_device_placement(&mut self, val: bool) -> &mut Self {
+        self.log_device_placement = Some(val);
+        self
+    }
+
+    /// Default timeout for blocking operations, in milliseconds.
+    pub fn operation_timeout_in_ms(&mut self, timeout: i64) -> &mut Self {
+        self.operation_timeout_in_ms = Some(timeout);
+        self
+    }
+
+    /// Fraction of the memory of each GPU to allocate up front.
+    pub fn gpu_memory_fraction(&mut self, fraction: f64) -> &mut Self {
+        self.gpu_memory_fraction = Some(fraction);
+        self
+    }
+
+    /// Allocate GPU memory as needed instead of up front.
+    pub fn gpu_allow_growth(&mut self, val: bool) -> &mut Self {
+        self.gpu_allow_growth = Some(val);
+        self
+    }
+
+    pub fn common_subexpression_elimination(&mut self, val: bool) -> &mut Self {
+        self.common_subexpression_elimination = Some(val);
+        self
+    }
+
+    pub fn constant_folding(&mut self, val: bool) -> &mut Self {
+        self.constant_folding = Some(val);
+        self
+    }
+
+    pub fn function_inlining(&mut self, val: bool) -> &mut Self {
+        self.function_inlining = Some(val);
+        self
+    }
+
+    /// Optimization level of the graph optimizer, this overrides the individual
+    /// optimizations set.
+    pub fn opt_level(&mut self, level: OptLevel) -> &mut Self {
+        self.opt_level = Some(level);
+        self
+    }
+
+    pub fn global_jit_level(&mut self, level: GlobalJitLevel) -> &mut Self {
+        self.global_jit_level = Some(level);
+        self
+    }
+
+    /// Serializes the configuration as a `ConfigProto` message.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut config = ProtoWriter::new();
+        for &(ref device_type, count) in &self.device_count {
+            let mut entry = ProtoWriter::new();
+            entry.string(1, device_type).int64(2, i64::from(count));
+            config.bytes(1, &entry.into_bytes());
+        }
+        if let Some(threads) = self.intra_op_parallelism_threads {
+            config.int64(2, i64::from(threads));
+        }
+        if let Some(threads) = self.inter_op_parallelism_threads {
+            config.int64(5, i64::from(threads));
+        }
+        if self.gpu_memory_fraction.is_some() || self.gpu_allow_growth.is_some() {
+            let mut gpu_options = ProtoWriter::new();
+            if let Some(fraction) = self.gpu_memory_fraction {
+                gpu_options.double(1, fraction);
+            }
+            if let Some(val) = self.gpu_allow_growth {
+                gpu_options.bool(4, val);
+            }
+            config.bytes(6, &gpu_options.into_bytes());
+        }
+        if let Some(val) = self.allow_soft_placement {
+            config.bool(7, val);
+        }
+        if let Some(val) = self.log_device_placement {
+            config.bool(8, val);
+        }
+        if let Some(val) = self.use_per_session_threads {
+            config.bool(9, val);
+        }
+        let optimizer_options = self.optimizer_options();
+        if !optimizer_options.is_empty() {
+            let mut graph_options = ProtoWriter::new();
+            graph_options.bytes(3, &optimizer_options);
+            config.bytes(10, &graph_options.into_bytes());
+        }
+        if let Some(timeout) = self.operation_timeout_in_ms {
+            config.int64(11, timeout);
+        }
+        config.into_bytes()
+    }
+
+    /// Returns session options set up with this configuration.
+    pub fn session_options(&self) -> Result<SessionOptions> {
+        let mut options = SessionOptions::new();
+        options.set_config(&self.to_bytes())?;
+        Ok(options)
+    }
+
+    /// Serializes the `OptimizerOptions` message of the graph options.
+    fn optimizer_options(&self) -> Vec<u8> {
+        let mut optimizer = ProtoWriter::new();
+        if let Some(val) = self.common_subexpression_elimination {
+            optimizer.bool(1, val);
+        }
+        if let Some(val) = self.constant_folding {
+            optimizer.bool(2, val);
+        }
+        if let Some(level) = self.opt_level {
+            let level = match level {
+                OptLevel::L1 => 0,
+                OptLevel::L0 => -1,
+            };
+            optimizer.int64(3, level);
+        }
+        if let Some(val) = self.function_inlining {
+            optimizer.bool(4, val);
+        }
+        if let Some(level) = self.global_jit_level {
+            let level = match level {
+                GlobalJitLevel::Default => 0,
+                GlobalJitLevel::Off => -1,
+                GlobalJitLevel::On1 => 1,
+                GlobalJitLevel::On2 => 2,
+            };
+            optimizer.int64(5, level);
+        }
+        optimizer.into_bytes()
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn test_session_config() {
+    use framework::protobuf::ProtoReader;
+
+    let mut config = SessionConfig::new();
+    config
+        .intra_op_parallelism_threads(4)
+        .inter_op_parallelism_threads(2)
+        .allow_soft_placement(true)
+        .opt_level(OptLevel::L0);
+    let bytes = config.to_bytes();
+
+    let mut reader = ProtoReader::new(&bytes);
+    let mut fields = vec![];
+    while let Some((field, val)) = reader.next_field().unwrap() {
+        match field {
+            10 => {
+                let graph_options = ProtoReader::new(val.as_bytes().unwrap()).next_field();
+                let optimizer = graph_options.unwrap().unwrap().1.as_bytes().unwrap();
+                let level = ProtoReader::new(optimizer).next_field().unwrap().unwrap();
+                assert_eq!(level.0, 3);
+                assert_eq!(level.1.as_varint().unwrap() as i64, -1);
+            }
+            _ => fields.push((field, val.as_varint().unwrap())),
+        }
+    }
+    assert_eq!(fields, vec![(2, 4), (5, 2), (7, 1)]);
+    assert!(config.session_options().is_ok());
+}
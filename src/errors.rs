@@ -1,17 +1,90 @@
 //! Error module.
 
-use tf::Status;
+use tf::{Code, DataType, Shape, Status};
 
 error_chain! {
-    errors { Stub }
+    errors {
+        /// An identifier with the same name already exists in the scope.
+        NameCollision(name: String) {
+            description("name collision")
+            display("name `{}` is already in use in this scope", name)
+        }
+        /// A variable was requested which doesn't exist in a scope set to reuse
+        /// variables, or which already exists in a scope not set to reuse them.
+        ReuseViolation(name: String, reuse: bool) {
+            description("variable reuse violation")
+            display("{}", if *reuse {
+                format!("variable `{}` does not exist, and the scope is set to reuse variables", name)
+            } else {
+                format!("variable `{}` already exists, and the scope is not set to reuse variables", name)
+            })
+        }
+        /// A tensor with a data type different from the expected one(s) was provided.
+        DTypeMismatch(context: String, expected: Vec<DataType>, found: DataType) {
+            description("data type mismatch")
+            display("{}: expected data type {}, found {:?}", context, format_dtypes(expected), found)
+        }
+        /// A tensor with a shape different from the expected one was provided.
+        ShapeMismatch(context: String, expected: Shape, found: Shape) {
+            description("shape mismatch")
+            display("{}: expected shape {:?}, found {:?}", context, expected, found)
+        }
+        /// The data type is not supported for the requested operation.
+        UnknownDType(dtype: DataType) {
+            description("unknown or unsupported data type")
+            display("unknown or unsupported data type {:?}", dtype)
+        }
+        /// A write operation was attempted on a scope with an active child scope.
+        LockedScope(name: String) {
+            description("locked scope")
+            display("scope `{}` is locked by a child scope, drop the child scope first", name)
+        }
+        /// A variable was expected but the tensor has no initializer.
+        MissingInitializer(name: String) {
+            description("missing initializer")
+            display("tensor `{}` has no initializer", name)
+        }
+        /// A tensor or an operation was looked up by name but doesn't exist in the graph.
+        NotFound(name: String) {
+            description("not found")
+            display("`{}` not found in the graph", name)
+        }
+        /// The arguments of an operation are not valid.
+        InvalidArgument(msg: String) {
+            description("invalid argument")
+            display("invalid argument: {}", msg)
+        }
+        /// A tensor can't be fed in a session run.
+        InvalidFeed(name: String, reason: String) {
+            description("invalid feed")
+            display("can't feed tensor `{}`: {}", name, reason)
+        }
+        /// A tensor can't be fetched in a session run.
+        InvalidFetch(name: String, reason: String) {
+            description("invalid fetch")
+            display("can't fetch tensor `{}`: {}", name, reason)
+        }
+        /// An error returned by the TensorFlow runtime.
+        Status(code: Code, msg: String) {
+            description("TensorFlow error")
+            display("TensorFlow error ({:?}): {}", code, msg)
+        }
+    }
 
     foreign_links {
         FFINulError(::std::ffi::NulError);
+        Io(::std::io::Error);
     }
 }
 
 impl From<Status> for Error {
     fn from(err: Status) -> Error {
-        Error::from(format!("{}", err))
+        let msg = ::std::error::Error::description(&err).to_owned();
+        Error::from(ErrorKind::Status(err.code(), msg))
     }
 }
+
+fn format_dtypes(dtypes: &[DataType]) -> String {
+    let dtypes: Vec<_> = dtypes.iter().map(|x| format!("{:?}", x)).collect();
+    dtypes.join(" or ")
+}
@@ -0,0 +1,357 @@
+//! Human-readable formatting of tensors, in the style of NumPy.
+
+use std::fmt;
+
+use tf::TensorType;
+
+use super::*;
+
+/// Options to format the elements of a tensor content.
+///
+/// ```ignore
+/// let mut options = PrintOptions::new();
+/// options.precision(3).edge_items(2);
+/// println!("{}", results[0].display_with(&options));
+/// ```
+///
+/// The precision can also be set with the formatter, e.g. `format!("{:.3}", content)`.
+#[derive(Debug, Clone)]
+pub struct PrintOptions {
+    precision: usize,
+    threshold: usize,
+    edge_items: usize,
+}
+
+impl Default for PrintOptions {
+    fn default() -> PrintOptions {
+        PrintOptions {
+            precision: 8,
+            threshold: 1000,
+            edge_items: 3,
+        }
+    }
+}
+
+impl PrintOptions {
+    pub fn new() -> PrintOptions {
+        PrintOptions::default()
+    }
+
+    /// Maximum number of digits after the decimal point of floating point numbers,
+    /// trailing zeros are not printed.
+    pub fn precision(&mut self, digits: usize) -> &mut Self {
+        self.precision = digits;
+        self
+    }
+
+    /// Tensors with more elements than this are summarized, eliding the middle
+    /// of each of the large dimensions.
+    pub fn threshold(&mut self, elements: usize) -> &mut Self {
+        self.threshold = elements;
+        self
+    }
+
+    /// Number of items shown at the beginning and the end of each summarized dimension.
+    pub fn edge_items(&mut self, items: usize) -> &mut Self {
+        self.edge_items = items;
+        self
+    }
+}
+
+/// Formatting of the elements of a tensor.
+trait DisplayElement: TensorType {
+    fn display(&self, precision: usize) -> String;
+}
+
+macro_rules! display_element {
+    ($($type:ty),*) => {$(
+        impl DisplayElement for $type {
+            fn display(&self, _precision: usize) -> String {
+                self.to_string()
+            }
+        }
+    )*}
+}
+
+display_element!(u8, i8, i16, i32, i64);
+
+macro_rules! display_quantized {
+    ($($type:ty => $inner:ty),*) => {$(
+        impl DisplayElement for $type {
+            fn display(&self, _precision: usize) -> String {
+                let val: $inner = (*self).into();
+                val.to_string()
+            }
+        }
+    )*}
+}
+
+display_quantized!(::QUInt8 => u8, ::QUInt16 => u16, ::QInt16 => i16, ::QInt32 => i32);
+
+impl DisplayElement for bool {
+    fn display(&self, _precision: usize) -> String {
+        let val = if *self { "True" } else { "False" };
+        val.to_owned()
+    }
+}
+
+impl DisplayElement for String {
+    fn display(&self, _precision: usize) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// Formats a float as NumPy does: trailing zeros are removed (keeping the decimal
+/// point), and very large or small values use the scientific notation.
+fn display_float(val: f64, precision: usize) -> String {
+    fn trim_zeros(digits: &str) -> &str {
+        if digits.contains('.') {
+            digits.trim_right_matches('0')
+        } else {
+            digits
+        }
+    }
+
+    if val.is_nan() {
+        return "nan".to_owned();
+    } else if val.is_infinite() {
+        return if val > 0. { "inf" } else { "-inf" }.to_owned();
+    }
+    let abs = val.abs();
+    if abs >= 1e16 || (abs != 0. && abs < 1e-4) {
+        let formatted = format!("{:.*e}", precision, val);
+        let (mantissa, exp) = formatted.split_at(formatted.find('e').unwrap());
+        let exp: i32 = exp[1..].parse().unwrap();
+        let mut mantissa = trim_zeros(mantissa).to_owned();
+        if !mantissa.contains('.') {
+            mantissa.push('.');
+        }
+        let sign = if exp < 0 { '-' } else { '+' };
+        format!("{}e{}{:02}", mantissa, sign, exp.abs())
+    } else {
+        let formatted = format!("{:.*}", precision, val);
+        let mut formatted = trim_zeros(&formatted).to_owned();
+        if !formatted.contains('.') {
+            formatted.push('.');
+        }
+        formatted
+    }
+}
+
+impl DisplayElement for f32 {
+    fn display(&self, precision: usize) -> String {
+        display_float(f64::from(*self), precision)
+    }
+}
+
+impl DisplayElement for f64 {
+    fn display(&self, precision: usize) -> String {
+        display_float(*self, precision)
+    }
+}
+
+impl DisplayElement for ::BFloat16 {
+    fn display(&self, precision: usize) -> String {
+        let val: f32 = (*self).into();
+        display_float(f64::from(val), precision)
+    }
+}
+
+fn display_complex(re: f64, im: f64, precision: usize) -> String {
+    let im_str = display_float(im, precision);
+    if im_str.starts_with('-') {
+        format!("{}{}j", display_float(re, precision), im_str)
+    } else {
+        format!("{}+{}j", display_float(re, precision), im_str)
+    }
+}
+
+impl DisplayElement for ::Complex32 {
+    fn display(&self, precision: usize) -> String {
+        display_complex(f64::from(self.re), f64::from(self.im), precision)
+    }
+}
+
+impl DisplayElement for ::Complex64 {
+    fn display(&self, precision: usize) -> String {
+        display_complex(self.re, self.im, precision)
+    }
+}
+
+/// Indexes of the items of a dimension which are shown, `None` stands for the elided ones.
+fn visible_items(size: usize, edge_items: usize, summarize: bool) -> Vec<Option<usize>> {
+    if summarize && size > 2 * edge_items {
+        (0..edge_items)
+            .map(Some)
+            .chain(Some(None))
+            .chain((size - edge_items..size).map(Some))
+            .collect()
+    } else {
+        (0..size).map(Some).collect()
+    }
+}
+
+/// Lays out the shown elements in nested brackets, the elements are consumed from
+/// `elements` in row-major order.
+struct Layout<'a, I> {
+    dims: &'a [usize],
+    strides: Vec<usize>,
+    edge_items: usize,
+    summarize: bool,
+    width: usize,
+    elements: I,
+}
+
+impl<'a, I: Iterator<Item = String>> Layout<'a, I> {
+    /// Calls `f` with the flat index of each shown element, in row-major order.
+    fn for_each_index<F: FnMut(usize)>(&self, depth: usize, offset: usize, f: &mut F) {
+        if depth == self.dims.len() {
+            return f(offset);
+        }
+        let items = visible_items(self.dims[depth], self.edge_items, self.summarize);
+        for idx in items.into_iter().flatten() {
+            self.for_each_index(depth + 1, offset + idx * self.strides[depth], f);
+        }
+    }
+
+    fn write(&mut self, out: &mut String, depth: usize) {
+        if depth == self.dims.len() {
+            let element = self.elements.next().unwrap_or_default();
+            for _ in element.chars().count()..self.width {
+                out.push(' ');
+            }
+            out.push_str(&element);
+            return;
+        }
+        out.push('[');
+        let items = visible_items(self.dims[depth], self.edge_items, self.summarize);
+        for (i, idx) in items.into_iter().enumerate() {
+            if i > 0 {
+                if depth + 1 == self.dims.len() {
+                    out.push(' ');
+                } else {
+                    for _ in depth + 1..self.dims.len() {
+                        out.push('\n');
+                    }
+                    for _ in 0..depth + 1 {
+                        out.push(' ');
+                    }
+                }
+            }
+            match idx {
+                Some(_) => self.write(out, depth + 1),
+                None => out.push_str("..."),
+            }
+        }
+        out.push(']');
+    }
+}
+
+fn display_tensor<T: DisplayElement>(
+    options: &PrintOptions,
+    precision: usize,
+    tensor: &TypedTensor<T>,
+) -> String {
+    let dims: Vec<usize> = tensor.dims().iter().map(|x| *x as usize).collect();
+    if dims.contains(&0) {
+        return "[]".to_owned();
+    }
+    let mut strides = vec![1; dims.len()];
+    for i in (0..dims.len().saturating_sub(1)).rev() {
+        strides[i] = strides[i + 1] * dims[i + 1];
+    }
+    let mut layout = Layout {
+        dims: &dims,
+        strides,
+        edge_items: options.edge_items,
+        summarize: tensor.len() > options.threshold,
+        width: 0,
+        elements: vec![].into_iter(),
+    };
+
+    let mut elements = vec![];
+    layout.for_each_index(0, 0, &mut |idx| elements.push(tensor[idx].display(precision)));
+    layout.width = elements.iter().map(|x| x.chars().count()).max().unwrap_or(0);
+    layout.elements = elements.into_iter();
+
+    let mut out = String::new();
+    layout.write(&mut out, 0);
+    out
+}
+
+/// Helper to display a tensor content with the given options, see
+/// `TensorContent::display_with`.
+pub struct TensorDisplay<'a> {
+    content: &'a TensorContent,
+    options: &'a PrintOptions,
+}
+
+impl<'a> fmt::Display for TensorDisplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let options = self.options;
+        let precision = f.precision().unwrap_or(options.precision);
+        let body = tensor_output_op!(self.content; display_tensor[options, precision,]);
+        writeln!(
+            f,
+            "Tensor(shape={:?}, dtype={:?})",
+            self.content.get_dims(),
+            self.content.get_datatype()
+        )?;
+        write!(f, "{}", body)
+    }
+}
+
+impl TensorContent {
+    /// Returns an object to display the tensor with the given options.
+    pub fn display_with<'a>(&'a self, options: &'a PrintOptions) -> TensorDisplay<'a> {
+        TensorDisplay {
+            content: self,
+            options,
+        }
+    }
+}
+
+/// Displays the shape and type of the tensor, followed by its elements in nested
+/// brackets, the middle of the large dimensions of big tensors are elided.
+impl fmt::Display for TensorContent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.display_with(&PrintOptions::default()), f)
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn test_display() {
+    fn tensor<T: TensorType>(dims: &[u64], values: &[T]) -> TypedTensor<T> {
+        let mut tensor = TypedTensor::new(dims);
+        tensor.clone_from_slice(values);
+        tensor
+    }
+
+    let content = TensorContent::from(tensor(&[2, 3], &[1_i32, -20, 3, 4, 5, 600]));
+    assert_eq!(
+        format!("{}", content),
+        "Tensor(shape=[2, 3], dtype=Int32)\n[[  1 -20   3]\n [  4   5 600]]"
+    );
+
+    let content = TensorContent::from(tensor(&[2, 1, 2], &[0.5_f32, 1., 2.25, -0.126]));
+    assert_eq!(
+        format!("{:.2}", content).lines().skip(1).collect::<Vec<_>>(),
+        vec!["[[[  0.5    1.]]", "", " [[ 2.25 -0.13]]]"]
+    );
+
+    let content = TensorContent::from(tensor(&[], &[::Complex64::new(1., -1e-5)]));
+    assert!(format!("{}", content).ends_with("\n1.-1.e-05j"));
+
+    let content = TensorContent::from(tensor(&[2], &[true, false]));
+    assert!(format!("{}", content).ends_with("\n[ True False]"));
+
+    let values: Vec<i64> = (0..2000).collect();
+    let content = TensorContent::from(tensor(&[2, 1000], &values));
+    let mut options = PrintOptions::new();
+    options.edge_items(2);
+    assert_eq!(
+        format!("{}", content.display_with(&options)).lines().nth(1),
+        Some("[[   0    1 ...  998  999]")
+    );
+}
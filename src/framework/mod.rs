@@ -14,30 +14,38 @@ use errors::*;
 macro_rules! to_typed_tensor {
     [$values:expr; $shape:expr] => {{
         let mut tensor = TypedTensor::<T>::new($shape);
-        for (i, v) in $values.iter().enumerate() {
-            tensor[i] = v.clone();
-        }
+        tensor.clone_from_slice($values);
         tensor
     }};
 }
 
-// TODO: this is very inefficient, implement efficient Clone for type
-// at the very least don't initialize with zeros
+// not zero-copy: the tensors own their buffer and the bindings can only allocate
+// zero initialized tensors, so the buffer is still written twice (in bulk)
 macro_rules! clone_tensor {
     ($val:ident) => {{
         let mut copy = TypedTensor::new($val.dims());
-        for (i, x) in $val.iter().enumerate() { 
-            copy[i] = x.clone();
-        }
+        copy.clone_from_slice(&$val[..]);
         copy
     }}
 }
 
 /////////////////////
 
+#[cfg(feature = "ndarray")]
+mod ndarray_conv;
+#[cfg(feature = "ndarray")]
+pub use self::ndarray_conv::*;
+
+mod display;
+pub use self::display::{PrintOptions, TensorDisplay};
+
+pub(crate) mod protobuf;
+
 mod scope;
 pub use self::scope::*;
 
+mod serialization;
+
 mod tensor_types;
 pub(crate) use self::tensor_types::*;
 pub use self::tensor_types::{DefinedShape, ShapeSize};
@@ -60,6 +68,20 @@ where
     fn fetch_input_lists(&self) -> &[(usize, Vec<Tensor>)];
     /// Get the attributes for this operation. Used while 'digesting' it.
     fn fetch_attributes<'s>(&'s self) -> &'s [(&str, bool, Attribute<'a>)];
+    /// Take the tensor attributes out of this operation, so they can be moved into the
+    /// graph without copying them. Used while 'digesting' it.
+    ///
+    /// The ops declared with `add_new_op!` move them out, this default implementation
+    /// can only clone them (copying their buffers), see `TensorContent`.
+    fn take_tensor_attributes(&mut self) -> Vec<(String, bool, Vec<TensorContent>)> {
+        self.fetch_attributes()
+            .iter()
+            .filter_map(|&(name, is_list, ref attribute)| match *attribute {
+                Attribute::Tensor(ref val) => Some((name.to_owned(), is_list, val.clone())),
+                _ => None,
+            })
+            .collect()
+    }
     #[doc(hidden)]
     /// Consumes self and returns output. Used when installing the op into the context.
     fn digest(self, context: &mut Scope, op: OperationData) -> Result<Self::Outputs>;
@@ -144,6 +166,105 @@ pub(crate) enum ControlOpKind {
     Other,
 }
 
+/// Inputs and attributes an operation was installed with, keyed by the operation name
+/// in the graph. Used to walk the graph backwards (e.g. when computing gradients).
+#[derive(Debug, Clone)]
+pub(crate) struct OpRecord {
+    pub op_type: &'static str,
+    /// Inputs in the same order they were added to the graph operation,
+    /// input lists are flattened in place.
+    pub inputs: Vec<Tensor>,
+    pub attributes: Vec<(String, AttrValue)>,
+}
+
+impl OpRecord {
+    pub fn new<'a, T>(op: &T) -> OpRecord
+    where
+        T: Operation<'a>,
+    {
+        let mut inputs = vec![];
+        let mut args_index = 0_usize;
+        let mut input_lists = op.fetch_input_lists().iter().peekable();
+        for input in op.fetch_inputs() {
+            while input_lists.peek().map(|&&(idx, _)| idx == args_index).unwrap_or(false) {
+                inputs.extend(input_lists.next().unwrap().1.iter().cloned());
+                args_index += 1;
+            }
+            inputs.push(*input);
+            args_index += 1;
+        }
+        for &(_, ref list) in input_lists {
+            inputs.extend(list.iter().cloned());
+        }
+
+        let mut attributes = vec![];
+        for &(name, _, ref attribute) in op.fetch_attributes() {
+            let value = match *attribute {
+                Attribute::String(val) => {
+                    AttrValue::String(val.iter().map(|x| (*x).to_owned()).collect())
+                }
+                Attribute::Int(val) => AttrValue::Int(val.to_vec()),
+                Attribute::Float(val) => AttrValue::Float(val.to_vec()),
+                Attribute::Bool(val) => AttrValue::Bool(val.to_vec()),
+                Attribute::Type(val) => AttrValue::Type(val.to_vec()),
+                Attribute::Shape(val) => AttrValue::Shape(val.to_vec()),
+                // tensor attributes are not kept around
+                Attribute::Tensor(_) => continue,
+            };
+            attributes.push((name.to_owned(), value));
+        }
+
+        OpRecord {
+            op_type: op.get_op_type_name(),
+            inputs,
+            attributes,
+        }
+    }
+
+    fn get_attr(&self, name: &str) -> Option<&AttrValue> {
+        self.attributes.iter().find(|&&(ref n, _)| n == name).map(|&(_, ref v)| v)
+    }
+
+    pub fn get_attr_bool(&self, name: &str) -> Option<bool> {
+        match self.get_attr(name) {
+            Some(&AttrValue::Bool(ref val)) => val.get(0).cloned(),
+            _ => None,
+        }
+    }
+
+    pub fn get_attr_int(&self, name: &str) -> Option<i64> {
+        match self.get_attr(name) {
+            Some(&AttrValue::Int(ref val)) => val.get(0).cloned(),
+            _ => None,
+        }
+    }
+
+    pub fn get_attr_string(&self, name: &str) -> Option<&str> {
+        match self.get_attr(name) {
+            Some(&AttrValue::String(ref val)) => val.get(0).map(|x| x.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn get_attr_type(&self, name: &str) -> Option<DataType> {
+        match self.get_attr(name) {
+            Some(&AttrValue::Type(ref val)) => val.get(0).cloned(),
+            _ => None,
+        }
+    }
+}
+
+/// Owned copy of an operation `Attribute`.
+#[derive(Debug, Clone)]
+pub(crate) enum AttrValue {
+    String(Vec<String>),
+    Int(Vec<i64>),
+    Float(Vec<f32>),
+    Bool(Vec<bool>),
+    Type(Vec<DataType>),
+    Shape(Vec<Shape>),
+}
+
 
 /// Tensor
 #[derive(Debug, Clone, Copy)]
@@ -169,7 +290,7 @@ impl Tensor {
                 initializer: None,
             })
         } else {
-            Err(Error::from(ErrorKind::Stub))
+            Err(Error::from(ErrorKind::MissingInitializer(self.get_name(context))))
         }
     }
 
@@ -190,7 +311,11 @@ impl Tensor {
             let shape = context.constant(&definition, &[definition.len() as i64], "")?;
             ::ops::array_ops::reshape(context, self, shape, "")
         } else {
-            Err(Error::from(ErrorKind::Stub))
+            Err(Error::from(ErrorKind::InvalidArgument(format!(
+                "can't set the not fully defined shape {:?} to tensor `{}`",
+                shape,
+                self.get_name(context)
+            ))))
         }
     }
 
@@ -249,13 +374,28 @@ pub struct Constant {
 }
 
 impl Constant {
+    /// Creates a new constant with an autogenerated name.
+    ///
+    /// ### Panics
+    /// If the constant can't be created, see `try_new`.
     pub fn new<TeS, T>(context: &mut Scope, value: &[T], shape: &[TeS]) -> Constant
     where
         T: TensorType,
         TeS: ShapeSize,
     {
-        let name = context.resolve_tensor_name(None, IdType::Constant, false).unwrap();
-        context.constant(value, shape, name).unwrap()
+        Constant::try_new(context, value, shape).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Creates a new constant with an autogenerated name.
+    ///
+    /// Returns an error if the scope is locked by an open child scope.
+    pub fn try_new<TeS, T>(context: &mut Scope, value: &[T], shape: &[TeS]) -> Result<Constant>
+    where
+        T: TensorType,
+        TeS: ShapeSize,
+    {
+        let name = context.resolve_tensor_name(None, IdType::Constant, false)?;
+        context.constant(value, shape, name)
     }
 
     pub fn get_name(&self, context: &Scope) -> String {
@@ -314,13 +454,32 @@ pub struct Variable {
 }
 
 impl Variable {
+    /// Creates a new variable with an autogenerated name, initialized to `initial_value`.
+    ///
+    /// ### Panics
+    /// If the variable can't be created, see `try_new`.
     pub fn new<TeS, T>(context: &mut Scope, initial_value: &[T], shape: &[TeS]) -> Variable
     where
         T: TensorType,
         TeS: ShapeSize,
     {
-        let values = context.constant(initial_value, shape, "").unwrap();
-        context.get_variable_with_initializer(values, false, "").unwrap()
+        Variable::try_new(context, initial_value, shape).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Creates a new variable with an autogenerated name, initialized to `initial_value`.
+    ///
+    /// Returns an error if the scope is locked by an open child scope.
+    pub fn try_new<TeS, T>(
+        context: &mut Scope,
+        initial_value: &[T],
+        shape: &[TeS],
+    ) -> Result<Variable>
+    where
+        T: TensorType,
+        TeS: ShapeSize,
+    {
+        let values = context.constant(initial_value, shape, "")?;
+        context.get_variable_with_initializer(values, false, "")
     }
 
     pub fn get_name(&self, context: &Scope) -> String {
@@ -346,7 +505,7 @@ impl Variable {
                 idx: tensor.idx,
             })
         } else {
-            Err(Error::from(ErrorKind::Stub))
+            Err(Error::from(ErrorKind::MissingInitializer(tensor.get_name(scope))))
         }
     }
 }
@@ -425,6 +584,9 @@ impl Into<NodeIdent> for TensorArray {
 
 
 /// An enumeration of the the different types of tensors.
+///
+/// Each variant owns its tensor buffer, so cloning a `TensorContent` copies the whole
+/// buffer; prefer moving them (e.g. into a `Constant` or a feed) for large tensors.
 #[derive(Debug)]
 pub enum TensorContent {
     Bool(TypedTensor<bool>),
@@ -470,15 +632,21 @@ impl Clone for TensorContent {
 
 macro_rules! unwrap_tensor_content {
     ($variant:ident, $name:tt, $type:ty) => {
+        /// ### Panics
+        /// If the tensor is of a different type, see `try_into_typed`.
         pub fn $name(self) -> TypedTensor<$type> {
-            match self {
-                TensorContent::$variant(val) => val,
-                _ => panic!()
-            }
-        } 
+            self.try_into_typed::<$type>().unwrap_or_else(|err| panic!("{}", err))
+        }
     }
 }
 
+/// Types of the elements of the tensors which can be stored in a `TensorContent`.
+pub trait FromTensorContent: TensorType {
+    /// Takes the typed tensor out of the content, returns an error if the content
+    /// holds a tensor of a different type.
+    fn try_from_content(content: TensorContent) -> Result<TypedTensor<Self>>;
+}
+
 macro_rules! from_tensor_to_content {
     ($type:ty, $id:ident) => {
         impl From<TypedTensor<$type>> for TensorContent {
@@ -486,6 +654,19 @@ macro_rules! from_tensor_to_content {
                 TensorContent::$id(tensor)
             }
         }
+
+        impl FromTensorContent for $type {
+            fn try_from_content(content: TensorContent) -> Result<TypedTensor<$type>> {
+                match content {
+                    TensorContent::$id(tensor) => Ok(tensor),
+                    other => Err(Error::from(ErrorKind::DTypeMismatch(
+                        "tensor content".to_owned(),
+                        vec![DataType::$id],
+                        other.get_datatype(),
+                    ))),
+                }
+            }
+        }
     }
 }
 
@@ -507,7 +688,7 @@ from_tensor_to_content!(::Complex32, Complex64);
 from_tensor_to_content!(::Complex64, Complex128);
 
 impl TensorContent {
-    fn get_datatype(&self) -> DataType {
+    pub(crate) fn get_datatype(&self) -> DataType {
         match *self {
             TensorContent::Float(_) => DataType::Float,
             TensorContent::Double(_) => DataType::Double,
@@ -528,12 +709,24 @@ impl TensorContent {
         }
     }
 
+    /// Takes the typed tensor out of the content, returns an error if the content holds
+    /// a tensor of a different type.
+    pub fn try_into_typed<T: FromTensorContent>(self) -> Result<TypedTensor<T>> {
+        T::try_from_content(self)
+    }
+
+    /// Returns the dimensions of the tensor.
+    pub(crate) fn get_dims(&self) -> &[u64] {
+        tensor_output_op!(self; TypedTensor::dims[])
+    }
+
     fn set_tensor_list_attr(
         new_op: &mut OperationDescription,
         name: &str,
-        val: &[TensorContent],
+        val: Vec<TensorContent>,
     ) -> Result<()> {
-        match val[0].get_datatype() {
+        let dtype = val[0].get_datatype();
+        match dtype {
             DataType::Bool => new_op.set_attr_tensor_list(name, collect_bool_tensor(val))?,
             DataType::Double => new_op.set_attr_tensor_list(name, collect_double_tensor(val))?,
             DataType::Float => new_op.set_attr_tensor_list(name, collect_float_tensor(val))?,
@@ -554,7 +747,7 @@ impl TensorContent {
             DataType::Complex128 => {
                 new_op.set_attr_tensor_list(name, collect_complex128_tensor(val))?
             }
-            _ => return Err(Error::from(ErrorKind::Stub)),
+            _ => return Err(Error::from(ErrorKind::UnknownDType(dtype))),
         }
         Ok(())
     }
@@ -562,9 +755,10 @@ impl TensorContent {
     fn set_tensor_attr(
         new_op: &mut OperationDescription,
         name: &str,
-        val: &[TensorContent],
+        val: Vec<TensorContent>,
     ) -> Result<()> {
-        match val[0].get_datatype() {
+        let dtype = val[0].get_datatype();
+        match dtype {
             DataType::Bool => {
                 new_op.set_attr_tensor(name, collect_bool_tensor(val).pop().unwrap())?
             }
@@ -609,7 +803,7 @@ impl TensorContent {
             DataType::Complex128 => {
                 new_op.set_attr_tensor(name, collect_complex128_tensor(val).pop().unwrap())?
             }
-            _ => return Err(Error::from(ErrorKind::Stub)),
+            _ => return Err(Error::from(ErrorKind::UnknownDType(dtype))),
         }
         Ok(())
     }
@@ -646,10 +840,10 @@ pub enum Attribute<'a> {
 
 macro_rules! collect_tensors {
     ($variant:ident, $name:tt, $type:ty) => {
-        fn $name(tensors: &[TensorContent]) -> Vec<TypedTensor<$type>> {
-            tensors.iter().map(|x| {
-                match *x {
-                    TensorContent::$variant(ref val) => clone_tensor!(val),
+        fn $name(tensors: Vec<TensorContent>) -> Vec<TypedTensor<$type>> {
+            tensors.into_iter().map(|x| {
+                match x {
+                    TensorContent::$variant(val) => val,
                     _ => panic!()
                 }
             }).collect::<Vec<_>>()
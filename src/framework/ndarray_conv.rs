@@ -0,0 +1,100 @@
+//! Conversions between tensors and `ndarray` arrays, enabled with the `ndarray` feature.
+
+use ndarray::{ArrayD, ArrayView, Dimension, IxDyn};
+use tf::TensorType;
+
+use super::*;
+
+/// Conversion of a tensor to an `ndarray` array with the same dimensions.
+pub trait ToArray<T> {
+    fn to_array(&self) -> Result<ArrayD<T>>;
+}
+
+impl<T: TensorType> ToArray<T> for TypedTensor<T> {
+    fn to_array(&self) -> Result<ArrayD<T>> {
+        let dims: Vec<usize> = self.dims().iter().map(|x| *x as usize).collect();
+        ArrayD::from_shape_vec(IxDyn(&dims), self.to_vec())
+            .map_err(|err| Error::from(ErrorKind::InvalidArgument(format!("{}", err))))
+    }
+}
+
+/// Conversion of an `ndarray` array to a tensor with the same dimensions.
+pub trait FromArray<T>: Sized {
+    fn from_array<D: Dimension>(array: ArrayView<T, D>) -> Self;
+}
+
+impl<T: TensorType> FromArray<T> for TypedTensor<T> {
+    fn from_array<D: Dimension>(array: ArrayView<T, D>) -> TypedTensor<T> {
+        let dims: Vec<u64> = array.shape().iter().map(|x| *x as u64).collect();
+        let mut tensor = TypedTensor::new(&dims);
+        if let Some(values) = array.as_slice() {
+            tensor.clone_from_slice(values);
+        } else {
+            // not in standard layout, copy in logical order
+            for (x, v) in tensor.iter_mut().zip(array.iter()) {
+                *x = v.clone();
+            }
+        }
+        tensor
+    }
+}
+
+impl TensorContent {
+    /// Creates a tensor content from an `ndarray` array.
+    pub fn from_array<T, D>(array: ArrayView<T, D>) -> TensorContent
+    where
+        T: TensorType,
+        D: Dimension,
+        TensorContent: From<TypedTensor<T>>,
+    {
+        TensorContent::from(TypedTensor::from_array(array))
+    }
+
+    /// Takes the tensor out of the content as an `ndarray` array, returns an error if
+    /// the content holds a tensor of a different type.
+    pub fn into_array<T: FromTensorContent>(self) -> Result<ArrayD<T>> {
+        self.try_into_typed::<T>()?.to_array()
+    }
+}
+
+impl Scope {
+    /// Create a new 'constant' tensor with the values and shape of the array.
+    pub fn constant_from_array<T, D, S>(
+        &mut self,
+        array: ArrayView<T, D>,
+        name: S,
+    ) -> Result<Constant>
+    where
+        T: TensorType,
+        D: Dimension,
+        S: AsRef<Path>,
+    {
+        let shape: Vec<i64> = array.shape().iter().map(|x| *x as i64).collect();
+        match array.as_slice() {
+            Some(values) => self.constant(values, &shape, name),
+            None => self.constant(&array.iter().cloned().collect::<Vec<_>>(), &shape, name),
+        }
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn test_ndarray_conversions() {
+    use ndarray::Array;
+
+    let array = Array::from_shape_vec((2, 3), vec![1_i32, 2, 3, 4, 5, 6]).unwrap();
+    let tensor = TypedTensor::from_array(array.view());
+    assert_eq!(tensor.dims(), &[2, 3]);
+    assert_eq!(&tensor[..], &[1, 2, 3, 4, 5, 6]);
+
+    // transposed views are copied in logical order
+    let transposed = TypedTensor::from_array(array.t());
+    assert_eq!(transposed.dims(), &[3, 2]);
+    assert_eq!(&transposed[..], &[1, 4, 2, 5, 3, 6]);
+
+    let content = TensorContent::from_array(array.view());
+    let back = content.clone().into_array::<i32>().unwrap();
+    assert_eq!(back.shape(), &[2, 3]);
+    assert!(back.iter().eq(array.iter()));
+    assert!(content.into_array::<f32>().is_err());
+}
@@ -0,0 +1,203 @@
+//! Minimal support for the protocol buffers wire format.
+//!
+//! Only what is needed to read the messages produced by TensorFlow which are not
+//! exposed by the C API (e.g. the signatures of a SavedModel), and to write the
+//! messages it takes as serialized buffers (e.g. the session configuration).
+
+use errors::*;
+
+fn invalid_message(msg: &str) -> Error {
+    Error::from(ErrorKind::InvalidArgument(format!("protobuf: {}", msg)))
+}
+
+/// A decoded field value.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum WireValue<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    LengthDelimited(&'a [u8]),
+    Fixed32(u32),
+}
+
+impl<'a> WireValue<'a> {
+    pub fn as_bytes(&self) -> Result<&'a [u8]> {
+        match *self {
+            WireValue::LengthDelimited(bytes) => Ok(bytes),
+            _ => Err(invalid_message("expected a length delimited field")),
+        }
+    }
+
+    pub fn as_str(&self) -> Result<&'a str> {
+        ::std::str::from_utf8(self.as_bytes()?)
+            .map_err(|_| invalid_message("invalid utf-8 string"))
+    }
+
+    pub fn as_varint(&self) -> Result<u64> {
+        match *self {
+            WireValue::Varint(val) => Ok(val),
+            _ => Err(invalid_message("expected a varint field")),
+        }
+    }
+}
+
+/// Iterates over the fields of an encoded message.
+pub(crate) struct ProtoReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ProtoReader<'a> {
+    pub fn new(buf: &'a [u8]) -> ProtoReader<'a> {
+        ProtoReader { buf, pos: 0 }
+    }
+
+    fn read_varint(&mut self) -> Result<u64> {
+        let mut val = 0_u64;
+        let mut shift = 0;
+        while shift < 64 {
+            let byte = *self.buf
+                .get(self.pos)
+                .ok_or_else(|| invalid_message("unexpected end of message"))?;
+            self.pos += 1;
+            val |= u64::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(val);
+            }
+            shift += 7;
+        }
+        Err(invalid_message("varint too long"))
+    }
+
+    fn read_slice(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.buf.len() - self.pos < len {
+            return Err(invalid_message("unexpected end of message"));
+        }
+        let slice = &self.buf[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(slice)
+    }
+
+    /// Returns the next field number and value, or `None` at the end of the message.
+    pub fn next_field(&mut self) -> Result<Option<(u32, WireValue<'a>)>> {
+        if self.pos >= self.buf.len() {
+            return Ok(None);
+        }
+        let key = self.read_varint()?;
+        let field = (key >> 3) as u32;
+        let value = match key & 0x7 {
+            0 => WireValue::Varint(self.read_varint()?),
+            1 => {
+                let mut val = 0_u64;
+                for (i, b) in self.read_slice(8)?.iter().enumerate() {
+                    val |= u64::from(*b) << (8 * i);
+                }
+                WireValue::Fixed64(val)
+            }
+            2 => {
+                let len = self.read_varint()? as usize;
+                WireValue::LengthDelimited(self.read_slice(len)?)
+            }
+            5 => {
+                let mut val = 0_u32;
+                for (i, b) in self.read_slice(4)?.iter().enumerate() {
+                    val |= u32::from(*b) << (8 * i);
+                }
+                WireValue::Fixed32(val)
+            }
+            wire_type => {
+                return Err(invalid_message(&format!("unsupported wire type {}", wire_type)))
+            }
+        };
+        Ok(Some((field, value)))
+    }
+}
+
+/// Decodes the values of a packed repeated field of varints.
+pub(crate) fn read_packed_varints(buf: &[u8]) -> Result<Vec<u64>> {
+    let mut reader = ProtoReader::new(buf);
+    let mut values = vec![];
+    while reader.pos < reader.buf.len() {
+        values.push(reader.read_varint()?);
+    }
+    Ok(values)
+}
+
+/// Decodes a map entry (`key` is field 1 and `value` is field 2) with string keys.
+pub(crate) fn read_map_entry(entry: &[u8]) -> Result<(&str, &[u8])> {
+    let mut reader = ProtoReader::new(entry);
+    let mut key = "";
+    let mut value: &[u8] = &[];
+    while let Some((field, val)) = reader.next_field()? {
+        match field {
+            1 => key = val.as_str()?,
+            2 => value = val.as_bytes()?,
+            _ => {}
+        }
+    }
+    Ok((key, value))
+}
+
+/// Encodes the fields of a message.
+#[derive(Debug, Default)]
+pub(crate) struct ProtoWriter {
+    buf: Vec<u8>,
+}
+
+impl ProtoWriter {
+    pub fn new() -> ProtoWriter {
+        ProtoWriter::default()
+    }
+
+    fn write_varint(&mut self, mut val: u64) {
+        while val >= 0x80 {
+            self.buf.push((val as u8 & 0x7F) | 0x80);
+            val >>= 7;
+        }
+        self.buf.push(val as u8);
+    }
+
+    fn write_key(&mut self, field: u32, wire_type: u8) {
+        self.write_varint(u64::from(field) << 3 | u64::from(wire_type));
+    }
+
+    /// Writes an unsigned integer field (`uint32`, `uint64` or an enum value).
+    pub fn varint(&mut self, field: u32, val: u64) -> &mut Self {
+        self.write_key(field, 0);
+        self.write_varint(val);
+        self
+    }
+
+    /// Writes a signed integer field (`int32` or `int64`).
+    pub fn int64(&mut self, field: u32, val: i64) -> &mut Self {
+        self.varint(field, val as u64)
+    }
+
+    pub fn bool(&mut self, field: u32, val: bool) -> &mut Self {
+        self.varint(field, val as u64)
+    }
+
+    pub fn double(&mut self, field: u32, val: f64) -> &mut Self {
+        self.write_key(field, 1);
+        let bits = val.to_bits();
+        for i in 0..8 {
+            self.buf.push((bits >> (8 * i)) as u8);
+        }
+        self
+    }
+
+    /// Writes a length delimited field (`bytes` or an embedded message).
+    pub fn bytes(&mut self, field: u32, val: &[u8]) -> &mut Self {
+        self.write_key(field, 2);
+        self.write_varint(val.len() as u64);
+        self.buf.extend_from_slice(val);
+        self
+    }
+
+    pub fn string(&mut self, field: u32, val: &str) -> &mut Self {
+        self.bytes(field, val.as_bytes())
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buf
+    }
+}
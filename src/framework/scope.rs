@@ -12,6 +12,9 @@ use ops::*;
 
 const DEFAULT_GRAPH_SEED: i32 = 87_654_321;
 
+/// Name of the global step variable in the root scope.
+pub(crate) const GLOBAL_STEP: &str = "global_step";
+
 /// Master context manager for building TensorFlow graphs and managing session execution.
 #[derive(Debug)]
 pub struct Scope {
@@ -23,6 +26,10 @@ pub struct Scope {
     pub(crate) own_scope: InternScope,
     pub(crate) control_context: ControlFlow,
     pub(crate) ops: Rc<RefCell<HashMap<NodeIdent, OperationData>>>,
+    /// inputs and attributes of installed ops, keyed by op name
+    pub(crate) op_records: Rc<RefCell<HashMap<String, OpRecord>>>,
+    /// gradient functions keyed by op type
+    pub(crate) gradient_registry: Rc<RefCell<GradientRegistry>>,
     reuse_variable: bool,
     not_variable_scope: bool,
     ignore_deps: bool,
@@ -46,6 +53,8 @@ impl Scope {
             locked: Rc::new(RefCell::new(false)),
             parent_lock: None,
             ops: Rc::new(RefCell::new(HashMap::new())),
+            op_records: Rc::new(RefCell::new(HashMap::new())),
+            gradient_registry: Rc::new(RefCell::new(GradientRegistry::new())),
             seed: None,
         }
     }
@@ -68,7 +77,8 @@ impl Scope {
                     // like a constant and viceversa
                     IdType::Constant | IdType::Variable => {
                         if self.own_scope.name_exists(&name) {
-                            return Err(Error::from(ErrorKind::Stub));
+                            let name = name.to_string_lossy().into_owned();
+                            return Err(Error::from(ErrorKind::NameCollision(name)));
                         }
                     }
                     IdType::Placeholder => {
@@ -78,7 +88,8 @@ impl Scope {
                             .find(|x| &x.full_name == &name)
                             .is_some()
                         {
-                            return Err(Error::from(ErrorKind::Stub));
+                            let name = name.to_string_lossy().into_owned();
+                            return Err(Error::from(ErrorKind::NameCollision(name)));
                         }
                     }
                     IdType::Operation(_) => {
@@ -189,21 +200,23 @@ impl Scope {
             locked: Rc::new(RefCell::new(false)),
             parent_lock: Some(self.locked.clone()),
             ops: self.ops.clone(),
+            op_records: self.op_records.clone(),
+            gradient_registry: self.gradient_registry.clone(),
             seed: self.seed.clone(),
         }
     }
 
-    /// If this scope is locked, don't allow any write operations and panic at runtime.
-    pub(crate) fn allow_writes(&self) {
+    /// If this scope is locked, don't allow any write operations and return an error.
+    pub(crate) fn allow_writes(&self) -> Result<()> {
         if *self.locked.borrow() {
             let scope_name = format!("{}", self.own_scope.name.display());
             let scope_name = match scope_name.as_str() {
                 "" => format!("{}", "root"),
                 _ => scope_name, 
             };
-            static MSG_0: &str = "tried to write at scope `";
-            static MSG_1: &str = "` while an other child scope was open";
-            panic!(format!("{}{}{}", MSG_0, scope_name, MSG_1))
+            Err(Error::from(ErrorKind::LockedScope(scope_name)))
+        } else {
+            Ok(())
         }
     }
 
@@ -215,12 +228,22 @@ impl Scope {
     ///
     /// Returns the output of the operations.
     #[doc(hidden)]
-    pub fn install<'a, T>(&mut self, op: T) -> Result<T::Outputs>
+    pub fn install<'a, T>(&mut self, mut op: T) -> Result<T::Outputs>
     where
         T: Operation<'a>,
     {
-        self.allow_writes();
+        self.allow_writes()?;
         let processed_inputs = self.process_op_inputs(&op);
+        let mut record = OpRecord::new(&op);
+        if let ControlFlow::CondContext(ref cond) = self.control_context {
+            // inside a cond branch the external inputs are fed through a switch
+            for input in &mut record.inputs {
+                if let Some(val) = cond.external_values.get(&input.ident) {
+                    *input = *val;
+                }
+            }
+        }
+        let tensor_attributes = op.take_tensor_attributes();
         let new_op = {
             let graph = &mut *self.graph.borrow_mut();
             let root = &*self.scopes.borrow();
@@ -277,13 +300,15 @@ impl Scope {
                             new_op.set_attr_shape(name, &val[0])?;
                         }
                     }
-                    Attribute::Tensor(ref val) => {
-                        if val.len() > 1 || is_list {
-                            TensorContent::set_tensor_list_attr(&mut new_op, name, val)?;
-                        } else if !val.is_empty() {
-                            TensorContent::set_tensor_attr(&mut new_op, name, val)?;
-                        }
-                    }
+                    // moved out of the op, see below
+                    Attribute::Tensor(_) => {}
+                }
+            }
+            for (name, is_list, val) in tensor_attributes {
+                if val.len() > 1 || is_list {
+                    TensorContent::set_tensor_list_attr(&mut new_op, &name, val)?;
+                } else if !val.is_empty() {
+                    TensorContent::set_tensor_attr(&mut new_op, &name, val)?;
                 }
             }
             for input in processed_inputs {
@@ -294,7 +319,9 @@ impl Scope {
             }
             let control_inputs = root.control_dependencies.iter().map(|x| &x.finished);
             add_control_input(&mut new_op, control_inputs);
-            new_op.finish()?
+            let new_op = new_op.finish()?;
+            self.op_records.borrow_mut().insert(name.to_str().unwrap().to_owned(), record);
+            new_op
         };
         op.digest(self, new_op)
     }
@@ -407,7 +434,7 @@ impl Scope {
     where
         S: AsRef<Path>,
     {
-        self.allow_writes();
+        self.allow_writes()?;
         if name_cmp!(name, "") && default_name.is_none() {
             return Err(Error::from(
                 "If default_name is None then name is required not be empty.",
@@ -428,11 +455,25 @@ impl Scope {
     /// Returns a context manager for use when defining an op.
     ///
     /// If the name argument is an empty string the name will be autogenerated.
+    ///
+    /// ### Panics
+    /// If a child of this scope is still open, see `try_name_scope`.
     pub fn name_scope<S>(&mut self, name: S, default: Option<S>) -> Scope
     where
         S: AsRef<Path>,
     {
-        self.allow_writes();
+        self.try_name_scope(name, default).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Returns a context manager for use when defining an op.
+    ///
+    /// Same as `name_scope`, but returns an error instead of panicking if a child
+    /// of this scope is still open.
+    pub fn try_name_scope<S>(&mut self, name: S, default: Option<S>) -> Result<Scope>
+    where
+        S: AsRef<Path>,
+    {
+        self.allow_writes()?;
         let name = if let Some(default) = default {
             self.resolve_new_scope_name(name, default.as_ref().to_str().unwrap())
         } else {
@@ -440,7 +481,7 @@ impl Scope {
         };
         let mut scope = self.as_new_child(name);
         scope.not_variable_scope = true;
-        scope
+        Ok(scope)
     }
 
     /// Gets an existing variable with these parameters or create a new one.
@@ -500,12 +541,12 @@ impl Scope {
                 DataType::Complex128 => {
                     array_ops::constant(g, n, TypedTensor::<::Complex64>::new(shape), &[])?
                 }
-                _ => return Err(Error::from(ErrorKind::Stub)),
+                _ => return Err(Error::from(ErrorKind::UnknownDType(dtype))),
             };
             Ok(op_data)
         }
 
-        self.allow_writes();
+        self.allow_writes()?;
         let new_var = self.resolve_tensor_name(Some(name.as_ref()), IdType::Variable, false)?;
 
         let var = if self.not_variable_scope {
@@ -520,20 +561,26 @@ impl Scope {
             if let Ok(idx) = var {
                 Ok(self.own_scope.variables[idx].1)
             } else {
-                Err(Error::from(ErrorKind::Stub))
+                let name = new_var.to_string_lossy().into_owned();
+                Err(Error::from(ErrorKind::ReuseViolation(name, true)))
             }
         } else if var.is_err() && !self.reuse_variable {
             // try making a new variable
             let rank_info = if let Some(shape) = shape {
                 shape.to_shape()
             } else {
-                // shape for a new variable must be specified
-                return Err(Error::from(ErrorKind::Stub));
+                return Err(Error::from(ErrorKind::InvalidArgument(format!(
+                    "shape for the new variable `{}` must be specified",
+                    new_var.display()
+                ))));
             };
             let dtype = if let Some(dtype) = dtype {
                 dtype
             } else {
-                return Err(Error::from("dtype not specified"));
+                return Err(Error::from(ErrorKind::InvalidArgument(format!(
+                    "dtype for the new variable `{}` must be specified",
+                    new_var.display()
+                ))));
             };
 
             let ident = NodeIdent::new();
@@ -603,13 +650,10 @@ impl Scope {
                             true,
                         )?,
                     ];
-                    // get previous existing control dependencies
-                    let cd = &self.scopes.borrow().control_dependencies;
-                    let control_inputs = cd.iter().map(|x| &x.finished).chain(init);
                     control_flow_ops::no_op_(
                         graph,
                         new_var.join("init_ctrl").to_str().unwrap(),
-                        control_inputs,
+                        init,
                     )?
                 };
                 // Register variable data.
@@ -623,7 +667,7 @@ impl Scope {
                         shape: rank_info,
                     },
                 );
-                self.scopes.borrow_mut().control_dependencies.push_front(ControlOp {
+                self.scopes.borrow_mut().var_initializers.push(ControlOp {
                     ident: NodeIdent::new(),
                     finished: init,
                     kind: ControlOpKind::VarInitializer,
@@ -631,7 +675,8 @@ impl Scope {
             }
             Ok(self._make_var_handle(ident, init_ident, new_var, dtype))
         } else {
-            Err(Error::from(ErrorKind::Stub))
+            let name = new_var.to_string_lossy().into_owned();
+            Err(Error::from(ErrorKind::ReuseViolation(name, false)))
         }
     }
 
@@ -646,7 +691,7 @@ impl Scope {
         S: AsRef<Path>,
         T: Into<NodeIdent>,
     {
-        self.allow_writes();
+        self.allow_writes()?;
         let new_var = self.resolve_tensor_name(Some(name.as_ref()), IdType::Variable, false)?;
         let initializer = initializer.into();
 
@@ -663,97 +708,185 @@ impl Scope {
             if let Ok(idx) = var {
                 Ok(self.own_scope.variables[idx].1)
             } else {
-                Err(Error::from(ErrorKind::Stub))
+                let name = new_var.to_string_lossy().into_owned();
+                Err(Error::from(ErrorKind::ReuseViolation(name, true)))
             }
         } else if var.is_err() && !self.reuse_variable {
-            let ident = NodeIdent::new();
-            let init;
-            let var;
-            let dtype;
-            let rank_info;
-            {
-                let graph = &mut *self.graph.borrow_mut();
-                let registry = &mut *self.registry.borrow_mut();
+            let (ident, dtype) = self.new_variable(initializer, validate_shape, &new_var)?;
+            Ok(self._make_var_handle(ident, initializer, new_var, dtype))
+        } else {
+            let name = new_var.to_string_lossy().into_owned();
+            Err(Error::from(ErrorKind::ReuseViolation(name, false)))
+        }
+    }
 
-                let initializer = {
-                    let initializer = registry.get(&initializer).unwrap();
-                    rank_info = graph.tensor_shape(Output {
-                        operation: initializer.data_origin.0.clone(),
-                        index: initializer.data_origin.1,
-                    })?;
-                    dtype = initializer.dtype;
-                    initializer.data_origin.clone()
-                };
+    /// Returns the global step variable, creating it if it doesn't exist yet.
+    ///
+    /// The global step is a scalar `Int64` variable initialized to zero which counts
+    /// the number of training steps. It is always created in the root scope with the
+    /// name `global_step`, independently of the scope from which this method is called,
+    /// so every part of the graph (optimizers, learning rate schedules, moving averages
+    /// or checkpoints) refers to the same counter.
+    pub fn get_or_create_global_step(&mut self) -> Result<Variable> {
+        self.allow_writes()?;
+        if let Some(global_step) = self.global_step() {
+            return Ok(global_step);
+        }
+        let initializer: NodeIdent = self.constant(&[0_i64], &[] as &[i32], "")?.into();
+        let name = PathBuf::from(GLOBAL_STEP);
+        let (ident, dtype) = self.new_variable(initializer, true, &name)?;
+        let var = Variable {
+            ident,
+            dtype,
+            initializer,
+            idx: 0,
+        };
+        {
+            let root = &mut *self.scopes.borrow_mut();
+            root.variables.push((name, var));
+            root.all_variables.push(var);
+            root.non_trainable.insert(ident);
+        }
+        Ok(var)
+    }
 
-                // variable op, not initialized!
-                var = {
-                    let deps = match self.control_context {
-                        ControlFlow::CondContext(ref cond) => {
-                            vec![&registry[&cond.pivot.ident].data_origin.0]
-                        }
-                        ControlFlow::WhileContext(ref cond) => {
-                            if cond.pivot_for_body.is_some() {
-                                vec![
-                                    &registry[&cond.pivot_for_body.as_ref().unwrap().ident]
-                                        .data_origin
-                                        .0,
-                                ]
-                            } else {
-                                vec![
-                                    &registry[&cond.pivot_for_pred.as_ref().unwrap().ident]
-                                        .data_origin
-                                        .0,
-                                ]
-                            }
+    /// Returns the global step variable if it has been created.
+    pub fn global_step(&self) -> Option<Variable> {
+        let find = |variables: &[(PathBuf, Variable)]| {
+            variables
+                .iter()
+                .find(|&&(ref name, _)| name.as_os_str() == GLOBAL_STEP)
+                .map(|&(_, var)| var)
+        };
+        let root = self.scopes.borrow();
+        find(&root.variables).or_else(|| {
+            if self.own_scope.name.as_os_str().is_empty() {
+                find(&self.own_scope.variables)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns all the variables created so far in the graph.
+    ///
+    /// See `trainable_variables` for the variables updated by the optimizers.
+    pub fn global_variables(&self) -> Vec<Variable> {
+        self.scopes.borrow().all_variables.clone()
+    }
+
+    /// Returns the variables created so far in the graph which are trained by the
+    /// optimizers, that is all of them except the ones marked as not trainable (the
+    /// global step, the optimizer slots or the moving averages shadow variables).
+    pub fn trainable_variables(&self) -> Vec<Variable> {
+        let mut vars = self.global_variables();
+        vars.retain(|var| self.is_trainable(var));
+        vars
+    }
+
+    /// Marks the variable as not trainable, it's not returned by `trainable_variables`
+    /// so the optimizers don't update it by default.
+    pub fn set_not_trainable(&mut self, var: &Variable) {
+        self.scopes.borrow_mut().non_trainable.insert(var.ident);
+    }
+
+    /// Returns false if the variable was marked as not trainable in this graph.
+    pub fn is_trainable(&self, var: &Variable) -> bool {
+        !self.scopes.borrow().non_trainable.contains(&var.ident)
+    }
+
+    /// Adds a new variable op with the given full name to the graph and returns its
+    /// identifier and type.
+    ///
+    /// The variable is initialized by the `initializer` tensor when the initialization
+    /// step is run.
+    fn new_variable(
+        &self,
+        initializer: NodeIdent,
+        validate_shape: bool,
+        new_var: &Path,
+    ) -> Result<(NodeIdent, DataType)> {
+        let ident = NodeIdent::new();
+        let init;
+        let var;
+        let dtype;
+        let rank_info;
+        {
+            let graph = &mut *self.graph.borrow_mut();
+            let registry = &mut *self.registry.borrow_mut();
+
+            let initializer = {
+                let initializer = registry.get(&initializer).unwrap();
+                rank_info = graph.tensor_shape(Output {
+                    operation: initializer.data_origin.0.clone(),
+                    index: initializer.data_origin.1,
+                })?;
+                dtype = initializer.dtype;
+                initializer.data_origin.clone()
+            };
+
+            // variable op, not initialized!
+            var = {
+                let deps = match self.control_context {
+                    ControlFlow::CondContext(ref cond) => {
+                        vec![&registry[&cond.pivot.ident].data_origin.0]
+                    }
+                    ControlFlow::WhileContext(ref cond) => {
+                        if cond.pivot_for_body.is_some() {
+                            vec![
+                                &registry[&cond.pivot_for_body.as_ref().unwrap().ident]
+                                    .data_origin
+                                    .0,
+                            ]
+                        } else {
+                            vec![
+                                &registry[&cond.pivot_for_pred.as_ref().unwrap().ident]
+                                    .data_origin
+                                    .0,
+                            ]
                         }
-                        ControlFlow::None => vec![],
-                    };
-                    init_ops::variable_(graph, new_var.to_str().unwrap(), dtype, &rank_info, deps)?
+                    }
+                    ControlFlow::None => vec![],
                 };
+                init_ops::variable_(graph, new_var.to_str().unwrap(), dtype, &rank_info, deps)?
+            };
 
-                // initializer
-                init = {
-                    let init = &[
-                        init_ops::assign_(
-                            graph,
-                            new_var.join("init").to_str().unwrap(),
-                            var.clone(),
-                            initializer,
-                            validate_shape,
-                        )?,
-                    ];
-
-                    // get previous existing control dependencies
-                    let cd = &self.scopes.borrow().control_dependencies;
-                    let control_inputs = cd.iter().map(|x| &x.finished).chain(init);
-                    control_flow_ops::no_op_(
+            // initializer
+            init = {
+                let init = &[
+                    init_ops::assign_(
                         graph,
-                        new_var.join("init_ctrl").to_str().unwrap(),
-                        control_inputs,
-                    )?
-                };
+                        new_var.join("init").to_str().unwrap(),
+                        var.clone(),
+                        initializer,
+                        validate_shape,
+                    )?,
+                ];
+                control_flow_ops::no_op_(
+                    graph,
+                    new_var.join("init_ctrl").to_str().unwrap(),
+                    init,
+                )?
+            };
 
-                // Register variable data
-                registry.insert(
-                    ident,
-                    TensorData {
-                        full_name: new_var.clone(),
-                        idtype: IdType::Variable,
-                        dtype,
-                        data_origin: (var, 0),
-                        shape: rank_info,
-                    },
-                );
-                self.scopes.borrow_mut().control_dependencies.push_front(ControlOp {
-                    ident: NodeIdent::new(),
-                    finished: init,
-                    kind: ControlOpKind::VarInitializer,
-                });
-            }
-            Ok(self._make_var_handle(ident, initializer, new_var, dtype))
-        } else {
-            Err(Error::from(ErrorKind::Stub))
+            // Register variable data
+            registry.insert(
+                ident,
+                TensorData {
+                    full_name: new_var.to_owned(),
+                    idtype: IdType::Variable,
+                    dtype,
+                    data_origin: (var, 0),
+                    shape: rank_info,
+                },
+            );
+            self.scopes.borrow_mut().var_initializers.push(ControlOp {
+                ident: NodeIdent::new(),
+                finished: init,
+                kind: ControlOpKind::VarInitializer,
+            });
         }
+        Ok((ident, dtype))
     }
 
     fn _make_var_handle(
@@ -770,6 +903,7 @@ impl Scope {
             initializer,
             idx: 0,
         };
+        self.scopes.borrow_mut().all_variables.push(var);
         if !self.not_variable_scope {
             // use local
             self.own_scope.variables.push((new_var, var));
@@ -793,15 +927,24 @@ impl Scope {
         T: TensorType,
         TeS: ShapeSize,
     {
-        self.allow_writes();
+        self.allow_writes()?;
+        let shape: &[u64] = &shape_as_u64(shape);
+        let num_elements = shape.iter().try_fold(1_u64, |acc, dim| acc.checked_mul(*dim));
+        if num_elements != Some(value.len() as u64) {
+            return Err(Error::from(ErrorKind::InvalidArgument(format!(
+                "constant of shape {:?} takes {} values, found {}",
+                shape,
+                num_elements.map_or("too many".to_owned(), |n| n.to_string()),
+                value.len()
+            ))));
+        }
+
         let graph = &mut *self.graph.borrow_mut();
         let registry = &mut *self.registry.borrow_mut();
 
         let full_name = self.resolve_tensor_name(Some(name.as_ref()), IdType::Constant, false)?;
         let ident = NodeIdent::new();
 
-        let shape: &[u64] = &shape_as_u64(shape);
-
         let data_origin = {
             let cd = &self.scopes.borrow().control_dependencies;
             match self.control_context {
@@ -864,18 +1007,57 @@ impl Scope {
     ///
     /// _Important:_ This tensor will produce an error if evaluated. Its value must be fed to the
     /// client session.
-    pub fn placeholder(&mut self, dtype: DataType) -> Tensor {
-        self.allow_writes();
+    pub fn placeholder(&mut self, dtype: DataType) -> Result<Tensor> {
+        self.new_placeholder(dtype, None, "")
+    }
+
+    /// Inserts a named placeholder for a tensor that will be always fed.
+    ///
+    /// The placeholder can be fed by name through `ClientSession::feed_by_name`. Returns an
+    /// error if the name is already in use in this scope.
+    pub fn placeholder_with_name<S>(&mut self, dtype: DataType, name: S) -> Result<Tensor>
+    where
+        S: AsRef<Path>,
+    {
+        self.new_placeholder(dtype, None, name)
+    }
+
+    /// Inserts a placeholder with a (possibly partially) known shape for a tensor that
+    /// will be always fed.
+    ///
+    /// The shape is checked when the tensor is fed to a `ClientSession`, and it's available
+    /// for shape inference of the operations built on top of this placeholder. Unknown
+    /// dimensions can be left as `None` when passing a `Shape`.
+    pub fn placeholder_with_shape<IS, S>(
+        &mut self,
+        dtype: DataType,
+        shape: IS,
+        name: S,
+    ) -> Result<Tensor>
+    where
+        IS: IntoShape,
+        S: AsRef<Path>,
+    {
+        self.new_placeholder(dtype, Some(shape.to_shape()), name)
+    }
+
+    fn new_placeholder<S: AsRef<Path>>(
+        &mut self,
+        dtype: DataType,
+        shape: Option<Shape>,
+        name: S,
+    ) -> Result<Tensor> {
+        self.allow_writes()?;
 
         let ident = NodeIdent::new();
-        let full_name = self.resolve_tensor_name(None, IdType::Placeholder, false).unwrap();
+        let full_name =
+            self.resolve_tensor_name(Some(name.as_ref()), IdType::Placeholder, false)?;
 
         let graph = &mut *self.graph.borrow_mut();
         let registry = &mut *self.registry.borrow_mut();
 
         let data_origin = (
-            array_ops::placeholder(graph, full_name.to_str().unwrap(), dtype)
-                .unwrap(),
+            array_ops::placeholder(graph, full_name.to_str().unwrap(), dtype, shape.as_ref())?,
             0,
         );
         registry.insert(
@@ -885,67 +1067,96 @@ impl Scope {
                 dtype,
                 idtype: IdType::Placeholder,
                 data_origin,
-                shape: Shape::from(None),
+                shape: shape.unwrap_or_else(|| Shape::from(None)),
             },
         );
 
-        Tensor {
+        Ok(Tensor {
             ident,
             idtype: IdType::Placeholder,
             dtype,
             idx: 0,
             initializer: None,
-        }
+        })
     }
 
     /// Returns a scope that specifies control dependencies.
+    ///
+    /// ### Panics
+    /// If a child of this scope is still open or any of the control inputs is not
+    /// in the graph, see `try_control_dependencies`.
     pub fn control_dependencies<'a, I, T: 'a>(&mut self, control_inputs: I) -> Scope
     where
         I: IntoIterator<Item = &'a T>,
         T: GetIdent,
     {
-        self.allow_writes();
-        let name = self.own_scope.name.clone();
-        let mut context = self.as_new_child(name);
-        let op_name = self.resolve_tensor_name(None, IdType::Operation("NoOp"), false).unwrap();
-
-        let registry = &*self.registry.borrow();
-        let existing_ops = &*self.ops.borrow();
-        let global = &mut self.scopes.borrow_mut().control_dependencies;
+        self.try_control_dependencies(control_inputs)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
 
-        let mut ops = vec![];
-        for control_input in control_inputs.into_iter() {
-            let ident = control_input.get_ident();
-            let ctrl = if let Some(op) = existing_ops.get(&ident) {
-                ops.push(op);
-                ControlOp {
-                    ident,
-                    finished: op.clone(),
-                    kind: ControlOpKind::Ops,
-                }
-            } else {
-                let finished = &registry[&ident].data_origin.0;
-                ops.push(finished);
-                ControlOp {
-                    ident,
-                    finished: finished.clone(),
-                    kind: ControlOpKind::Other,
-                }
-            };
-            context.own_scope.control_dependencies.push_back(ctrl.clone());
-            global.push_back(ctrl);
-        }
+    /// Returns a scope that specifies control dependencies.
+    ///
+    /// Same as `control_dependencies`, but returns an error instead of panicking.
+    pub fn try_control_dependencies<'a, I, T: 'a>(&mut self, control_inputs: I) -> Result<Scope>
+    where
+        I: IntoIterator<Item = &'a T>,
+        T: GetIdent,
+    {
+        self.allow_writes()?;
+        let op_name = self.resolve_tensor_name(None, IdType::Operation("NoOp"), false)?;
+
+        let (ctrls, ctrl_group) = {
+            let registry = &*self.registry.borrow();
+            let existing_ops = &*self.ops.borrow();
+
+            let mut ops = vec![];
+            let mut ctrls = vec![];
+            for control_input in control_inputs.into_iter() {
+                let ident = control_input.get_ident();
+                let ctrl = if let Some(op) = existing_ops.get(&ident) {
+                    ops.push(op);
+                    ControlOp {
+                        ident,
+                        finished: op.clone(),
+                        kind: ControlOpKind::Ops,
+                    }
+                } else if let Some(data) = registry.get(&ident) {
+                    let finished = &data.data_origin.0;
+                    ops.push(finished);
+                    ControlOp {
+                        ident,
+                        finished: finished.clone(),
+                        kind: ControlOpKind::Other,
+                    }
+                } else {
+                    return Err(Error::from(ErrorKind::InvalidArgument(format!(
+                        "control input {:?} not found in the graph",
+                        ident
+                    ))));
+                };
+                ctrls.push(ctrl);
+            }
 
-        let ctrl_group = {
             let graph = &mut *self.graph.borrow_mut();
-            ControlOp {
+            let ctrl_group = ControlOp {
                 ident: NodeIdent::new(),
-                finished: control_flow_ops::no_op_(graph, op_name.to_str().unwrap(), ops).unwrap(),
+                finished: control_flow_ops::no_op_(graph, op_name.to_str().unwrap(), ops)?,
                 kind: ControlOpKind::Ops,
-            }
+            };
+            (ctrls, ctrl_group)
         };
-        global.push_front(ctrl_group);
-        context
+
+        let name = self.own_scope.name.clone();
+        let mut context = self.as_new_child(name);
+        let global = &mut self.scopes.borrow_mut().control_dependencies;
+        for ctrl in ctrls {
+            context.own_scope.control_dependencies.push_back(ctrl.clone());
+            global.push_back(ctrl);
+        }
+        // track the group in the new scope too, so it's popped when the scope is dropped
+        context.own_scope.control_dependencies.push_back(ctrl_group.clone());
+        global.push_back(ctrl_group);
+        Ok(context)
     }
 
     /// Returns a scope which ignores all previously set up control dependencies.
@@ -962,7 +1173,7 @@ impl Scope {
         S: AsRef<Path>,
         Tx: GetIdent,
     {
-        self.allow_writes();
+        self.allow_writes()?;
 
         let graph = &mut *self.graph.borrow_mut();
         let registry = &mut *self.registry.borrow_mut();
@@ -970,6 +1181,13 @@ impl Scope {
 
         let (dtype, idtype, data_origin, full_name) = {
             let src = &registry[&tensor.get_ident()];
+            let input = Tensor {
+                ident: tensor.get_ident(),
+                idtype: src.idtype,
+                dtype: src.dtype,
+                idx: src.data_origin.1,
+                initializer: None,
+            };
             let full_name = self.resolve_tensor_name(Some(name.as_ref()), src.idtype, false)?;
             let data_origin = (
                 array_ops::identity(
@@ -980,6 +1198,14 @@ impl Scope {
                 )?,
                 0,
             );
+            self.op_records.borrow_mut().insert(
+                full_name.to_str().unwrap().to_owned(),
+                OpRecord {
+                    op_type: "Identity",
+                    inputs: vec![input],
+                    attributes: vec![],
+                },
+            );
             (src.dtype, src.idtype, data_origin, full_name)
         };
 
@@ -1067,6 +1293,184 @@ impl Scope {
         self.scopes.borrow_mut().unfeedable.insert(op.into());
     }
 
+    /// Returns false if the tensor was marked as unfetchable in this graph.
+    pub(crate) fn is_fetchable(&self, ident: &NodeIdent) -> bool {
+        !(self.scopes.borrow().unfetchable.contains(ident)
+            || self.own_scope.unfetchable.contains(ident))
+    }
+
+    /// Returns false if the tensor was marked as unfeedable in this graph.
+    pub(crate) fn is_feedable(&self, ident: &NodeIdent) -> bool {
+        !(self.scopes.borrow().unfeedable.contains(ident)
+            || self.own_scope.unfeedable.contains(ident))
+    }
+
+    /// Registers the function which computes the gradients for the operations of type
+    /// `op_type` (as returned by `Operation::get_op_type_name`) in this graph.
+    ///
+    /// Returns an error if the operation type already has a gradient registered.
+    pub fn register_gradient<S: AsRef<str>>(&mut self, op_type: S, grad_fn: GradFn) -> Result<()> {
+        self.gradient_registry.borrow_mut().register(op_type.as_ref(), grad_fn)
+    }
+
+    /// Marks the operations of type `op_type` as not differentiable, gradients are not
+    /// backpropagated through them.
+    ///
+    /// Returns an error if the operation type already has a gradient registered.
+    pub fn not_differentiable<S: AsRef<str>>(&mut self, op_type: S) -> Result<()> {
+        self.gradient_registry.borrow_mut().not_differentiable(op_type.as_ref())
+    }
+
+    /// Serializes the graph built so far as a `GraphDef` protocol buffer and writes
+    /// it to `path`.
+    pub fn export_graph_def<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        use std::fs::File;
+        use std::io::Write;
+
+        let graph_def = self.graph.borrow().graph_def()?;
+        File::create(path)?.write_all(&graph_def)?;
+        Ok(())
+    }
+
+    /// Imports a serialized `GraphDef` protocol buffer into the graph of this scope.
+    ///
+    /// The names of the imported operations are prefixed with `prefix` (if not empty).
+    /// Every output of the imported operations is registered, so it can be fed or
+    /// fetched through a `ClientSession` like any other tensor.
+    ///
+    /// Returns a map from the names of the imported outputs (e.g. "prefix/op_name:0")
+    /// to their tensors.
+    pub fn import_graph_def<S: AsRef<str>>(
+        &mut self,
+        graph_def: &[u8],
+        prefix: S,
+    ) -> Result<HashMap<String, Tensor>> {
+        use tf::ImportGraphDefOptions;
+
+        self.allow_writes()?;
+        let existing: HashSet<String> = {
+            let graph = &mut *self.graph.borrow_mut();
+            let existing = graph.operation_iter().filter_map(|op| op.name().ok()).collect();
+            let mut options = ImportGraphDefOptions::new();
+            if !prefix.as_ref().is_empty() {
+                options.set_prefix(prefix.as_ref())?;
+            }
+            graph.import_graph_def(graph_def, &options)?;
+            existing
+        };
+        self.register_graph_ops(&existing)
+    }
+
+    /// Registers the outputs of all the operations in the graph, except those named
+    /// in `skip`, e.g. after importing a graph.
+    ///
+    /// Returns a map from the names of the outputs ("op_name:idx") to their tensors.
+    pub(crate) fn register_graph_ops(
+        &mut self,
+        skip: &HashSet<String>,
+    ) -> Result<HashMap<String, Tensor>> {
+        let graph = &*self.graph.borrow();
+        let registry = &mut *self.registry.borrow_mut();
+        let mut imported = HashMap::new();
+        for op in graph.operation_iter() {
+            let op_name = op.name()?;
+            if skip.contains(&op_name) {
+                continue;
+            }
+            for idx in 0..op.num_outputs() {
+                let tensor = register_graph_output(graph, registry, &op, idx as i32)?;
+                self.own_scope.ops.push((PathBuf::from(&op_name), tensor.ident));
+                imported.insert(format!("{}:{}", op_name, idx), tensor);
+            }
+        }
+        Ok(imported)
+    }
+
+    /// Returns the tensor with the given name, e.g. "scope/op_name:0".
+    ///
+    /// Both the tensors created through the API and the outputs of operations which were
+    /// added directly to the graph (e.g. imported from a `GraphDef`) are found.
+    ///
+    /// Returns an `InvalidArgument` error if the name is not a tensor name, or a
+    /// `NotFound` error if there is no such tensor in the graph.
+    pub fn get_tensor_by_name(&self, name: &str) -> Result<Tensor> {
+        let mut parts = name.rsplitn(2, ':');
+        let (idx, op_name) = match (parts.next(), parts.next()) {
+            (Some(idx), Some(op_name)) => match idx.parse::<i32>() {
+                Ok(idx) => (idx, op_name),
+                Err(_) => {
+                    return Err(Error::from(ErrorKind::InvalidArgument(format!(
+                        "invalid tensor name `{}`, the output index is not a number",
+                        name
+                    ))))
+                }
+            },
+            _ => {
+                return Err(Error::from(ErrorKind::InvalidArgument(format!(
+                    "`{}` is not a tensor name, tensor names must have the form \
+                     `<op_name>:<output_index>`",
+                    name
+                ))))
+            }
+        };
+
+        {
+            let registry = &*self.registry.borrow();
+            let found = registry.iter().find(|&(_, data)| {
+                let (ref op, op_idx) = data.data_origin;
+                op_idx == idx && op.name().map(|n| n == op_name).unwrap_or(false)
+            });
+            if let Some((ident, data)) = found {
+                let initializer = if data.idtype == IdType::Variable {
+                    self.global_variables()
+                        .into_iter()
+                        .find(|var| var.ident == *ident)
+                        .map(|var| var.initializer)
+                } else {
+                    None
+                };
+                return Ok(Tensor {
+                    ident: *ident,
+                    idtype: data.idtype,
+                    dtype: data.dtype,
+                    idx,
+                    initializer,
+                });
+            }
+        }
+
+        let graph = &*self.graph.borrow();
+        let op = graph
+            .operation_by_name(op_name)?
+            .ok_or_else(|| Error::from(ErrorKind::NotFound(name.to_owned())))?;
+        if idx < 0 || idx >= op.num_outputs() as i32 {
+            return Err(Error::from(ErrorKind::NotFound(name.to_owned())));
+        }
+        register_graph_output(graph, &mut *self.registry.borrow_mut(), &op, idx)
+    }
+
+    /// Returns the identifier of the operation with the given name, e.g. "scope/op_name".
+    ///
+    /// The identifier can be used as a control dependency or as a target of a session
+    /// run. Returns a `NotFound` error if there is no such operation in the graph.
+    pub fn get_operation_by_name(&self, name: &str) -> Result<NodeIdent> {
+        if let Some((ident, _)) = self.ops
+            .borrow()
+            .iter()
+            .find(|&(_, op)| op.name().map(|n| n == name).unwrap_or(false))
+        {
+            return Ok(*ident);
+        }
+
+        let op = self.graph
+            .borrow()
+            .operation_by_name(name)?
+            .ok_or_else(|| Error::from(ErrorKind::NotFound(name.to_owned())))?;
+        let ident = NodeIdent::new();
+        self.ops.borrow_mut().insert(ident, op);
+        Ok(ident)
+    }
+
     /// Consumes self and returns underlying graph if it's a unique reference, otherwise
     /// will return a Rc pointer to it.
     pub fn unwrap_graph(mut self) -> ::std::result::Result<Graph, Rc<RefCell<Graph>>> {
@@ -1097,8 +1501,10 @@ impl ::std::ops::Drop for Scope {
             ops,
             inner_scopes,
             control_dependencies,
+            var_initializers,
             unfetchable,
             unfeedable,
+            ..
         } = new_scope;
         // find if this scope already exists
         if let Some(parent) = find_parent_scope(&mut global.inner_scopes, &name, 0) {
@@ -1112,6 +1518,7 @@ impl ::std::ops::Drop for Scope {
         let original_deps = global.control_dependencies.len() - control_dependencies.len();
         global.control_dependencies.truncate(original_deps);
         // add to global scope:
+        global.var_initializers.extend(var_initializers);
         global.unfetchable.extend(unfetchable);
         global.unfeedable.extend(unfeedable);
         if let Some(lock) = self.parent_lock.as_ref() {
@@ -1125,6 +1532,46 @@ enum OpInput {
     Single(Output),
 }
 
+/// Adds the output `idx` of an operation which was added directly to the graph to the
+/// registry, so it can be used as any other tensor.
+fn register_graph_output(
+    graph: &Graph,
+    registry: &mut HashMap<NodeIdent, TensorData>,
+    op: &OperationData,
+    idx: i32,
+) -> Result<Tensor> {
+    let op_name = op.name()?;
+    let idtype = match op.op_type()?.as_str() {
+        "Const" => IdType::Constant,
+        "Placeholder" | "PlaceholderV2" | "PlaceholderWithDefault" => IdType::Placeholder,
+        "Variable" | "VariableV2" | "VarHandleOp" => IdType::Variable,
+        _ => IdType::Operation("Import"),
+    };
+    let ident = NodeIdent::new();
+    let dtype = op.output_type(idx as usize);
+    let shape = graph.tensor_shape(Output {
+        operation: op.clone(),
+        index: idx,
+    })?;
+    registry.insert(
+        ident,
+        TensorData {
+            full_name: PathBuf::from(op_name),
+            dtype,
+            idtype,
+            data_origin: (op.clone(), idx),
+            shape,
+        },
+    );
+    Ok(Tensor {
+        ident,
+        idtype,
+        dtype,
+        idx,
+        initializer: None,
+    })
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct InternScope {
     /// Full path for this scope.
@@ -1139,10 +1586,16 @@ pub(crate) struct InternScope {
     inner_scopes: Vec<Box<InternScope>>,
     /// Control dependencies in this scope
     pub(crate) control_dependencies: VecDeque<ControlOp>,
+    /// Variable initializers, in creation order.
+    pub(crate) var_initializers: Vec<ControlOp>,
     /// Unfetchable tensors.
     unfetchable: HashSet<NodeIdent>,
     /// Unfeedable tensors.
     unfeedable: HashSet<NodeIdent>,
+    /// Every variable in the graph, in creation order (only kept in the global scope).
+    all_variables: Vec<Variable>,
+    /// Variables which are not trained by the optimizers (only kept in the global scope).
+    non_trainable: HashSet<NodeIdent>,
 }
 
 impl InternScope {
@@ -1154,8 +1607,11 @@ impl InternScope {
             inner_scopes: vec![],
             ops: vec![],
             control_dependencies: VecDeque::new(),
+            var_initializers: vec![],
             unfetchable: HashSet::new(),
             unfeedable: HashSet::new(),
+            all_variables: vec![],
+            non_trainable: HashSet::new(),
         }
     }
 
@@ -1228,6 +1684,135 @@ mod test {
     #![allow(unused_imports)]
     use super::*;
 
+    #[test]
+    fn export_import_graph_def() {
+        use std::env;
+        use std::fs::File;
+        use std::io::Read;
+
+        let path = env::temp_dir().join(format!("tf_rs_graph_def_{}.pb", ::std::process::id()));
+        {
+            let mut root = Scope::new();
+            let x = root.constant(&[1_i32], &[] as &[i32], "x").unwrap();
+            let y = root.constant(&[2_i32], &[] as &[i32], "y").unwrap();
+            add(&mut root, x, y, "sum").unwrap();
+            root.export_graph_def(&path).unwrap();
+        }
+        let mut graph_def = vec![];
+        File::open(&path).unwrap().read_to_end(&mut graph_def).unwrap();
+        ::std::fs::remove_file(&path).unwrap();
+
+        let mut context = Scope::new();
+        let imported = context.import_graph_def(&graph_def, "imported").unwrap();
+        let op = imported["imported/sum:0"];
+        let results = test_suite!(run_op: [op]; context, input: {});
+        test_suite!(results; assert: {[0;Int32] == [3_i32]});
+    }
+
+    #[test]
+    fn name_collision() {
+        let mut root = Scope::new();
+        root.constant(&[1_i32], &[] as &[i32], "x").unwrap();
+        match *root.constant(&[2_i32], &[] as &[i32], "x").unwrap_err().kind() {
+            ErrorKind::NameCollision(ref name) => assert_eq!(name, "x"),
+            ref err => panic!("unexpected error: {}", err),
+        }
+        match *root.get_variable_with_initializer(NodeIdent::new(), true, "x").unwrap_err().kind() {
+            ErrorKind::NameCollision(_) => {}
+            ref err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn constant_shape() {
+        let mut root = Scope::new();
+        match *root.constant(&[1_i32, 2, 3], &[2, 2], "x").unwrap_err().kind() {
+            ErrorKind::InvalidArgument(_) => {}
+            ref err => panic!("unexpected error: {}", err),
+        }
+        assert!(root.constant(&[1_i32, 2, 3, 4, 5], &[2, 2], "x").is_err());
+        assert!(root.constant(&[1_i32], &[::std::i64::MAX, 4], "x").is_err());
+        assert!(root.constant(&[1_i32, 2, 3, 4], &[2, 2], "x").is_ok());
+    }
+
+    #[test]
+    fn locked_scope() {
+        let mut root = Scope::new();
+        let child = root.name_scope("child", None);
+        match *root.constant(&[1_i32], &[] as &[i32], "x").unwrap_err().kind() {
+            ErrorKind::LockedScope(ref name) => assert_eq!(name, "root"),
+            ref err => panic!("unexpected error: {}", err),
+        }
+        assert!(Constant::try_new(&mut root, &[1_i32], &[] as &[i32]).is_err());
+        assert!(Variable::try_new(&mut root, &[1_i32], &[] as &[i32]).is_err());
+        assert!(root.placeholder(DataType::Int32).is_err());
+        assert!(root.try_name_scope("other", None).is_err());
+        drop(child);
+        assert!(root.constant(&[1_i32], &[] as &[i32], "x").is_ok());
+    }
+
+    #[test]
+    fn get_by_name() {
+        let mut root = Scope::new();
+        let x = {
+            let foo = &mut root.name_scope("foo", None);
+            foo.constant(&[1_i32], &[] as &[i32], "x").unwrap()
+        };
+        let found = root.get_tensor_by_name("foo_0/x:0").unwrap();
+        assert_eq!(found.ident, x.ident);
+        assert_eq!(found.dtype, DataType::Int32);
+        match *root.get_tensor_by_name("foo_0/x").unwrap_err().kind() {
+            ErrorKind::InvalidArgument(_) => {}
+            ref err => panic!("unexpected error: {}", err),
+        }
+        match *root.get_tensor_by_name("foo_0/x:1").unwrap_err().kind() {
+            ErrorKind::NotFound(ref name) => assert_eq!(name, "foo_0/x:1"),
+            ref err => panic!("unexpected error: {}", err),
+        }
+        match *root.get_tensor_by_name("bar/x:0").unwrap_err().kind() {
+            ErrorKind::NotFound(_) => {}
+            ref err => panic!("unexpected error: {}", err),
+        }
+
+        // the operation identifier is not the identifier of its output
+        let op = root.get_operation_by_name("foo_0/x").unwrap();
+        assert_ne!(op, x.ident);
+        assert!(root.ops.borrow().contains_key(&op));
+        assert_eq!(root.get_operation_by_name("foo_0/x").unwrap(), op);
+
+        let init = root.constant(&[1_i32], &[] as &[i32], "").unwrap();
+        let w = root.get_variable_with_initializer(init, true, "w").unwrap();
+        let found = root.get_tensor_by_name("w:0").unwrap();
+        assert_eq!(found.ident, w.ident);
+        assert_eq!(found.get_initializer(&root).unwrap().ident, init.ident);
+
+        {
+            let graph = &mut *root.graph.borrow_mut();
+            graph.new_operation("NoOp", "no_op").unwrap().finish().unwrap();
+        }
+        let no_op = root.get_operation_by_name("no_op").unwrap();
+        assert_eq!(root.get_operation_by_name("no_op").unwrap(), no_op);
+        match *root.get_operation_by_name("missing").unwrap_err().kind() {
+            ErrorKind::NotFound(ref name) => assert_eq!(name, "missing"),
+            ref err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn global_variables() {
+        let mut root = Scope::new();
+        let init = root.constant(&[1_i32], &[] as &[i32], "").unwrap();
+        let w = root.get_variable_with_initializer(init, true, "w").unwrap();
+        let foo = &mut root.variable_scope("foo", None, None).unwrap();
+        let v = foo.get_variable_with_initializer(init, true, "v").unwrap();
+        let bar = &mut foo.name_scope("bar", None);
+        // the variables of the open parent scopes are found
+        assert_eq!(bar.global_variables(), vec![w, v]);
+        let step = bar.get_or_create_global_step().unwrap();
+        assert_eq!(bar.global_variables(), vec![w, v, step]);
+        assert_eq!(bar.trainable_variables(), vec![w, v]);
+    }
+
     #[ignore]
     #[test]
     fn scope_management() {
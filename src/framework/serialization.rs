@@ -0,0 +1,1081 @@
+//! Serialization of tensors to the TensorFlow `TensorProto` wire format and to the
+//! NumPy `.npy` and `.npz` formats.
+
+use std::fs::File;
+use std::io::{Read, Write};
+
+use tf::TensorType;
+
+use super::*;
+use super::protobuf::{read_packed_varints, ProtoReader, ProtoWriter, WireValue};
+
+/// Encoding of the values in the typed fields of a `TensorProto`.
+#[derive(Debug, Clone, Copy)]
+enum Wire {
+    Varint,
+    Fixed32,
+    Fixed64,
+}
+
+/// Element types with a fixed size little endian representation.
+trait Fixed: Sized {
+    const SIZE: usize;
+    /// `DataType` enum value in a `TensorProto`.
+    const PROTO_DTYPE: u64;
+    /// Field of a `TensorProto` which holds the values if there is no `tensor_content`.
+    const PROTO_FIELD: u32;
+    const PROTO_WIRE: Wire;
+    /// Number of values of the typed field per element (2 for complex numbers).
+    const PROTO_VALS: usize;
+    const NPY_DESCR: &'static str;
+
+    fn write_le(&self, buf: &mut Vec<u8>);
+    fn read_le(bytes: &[u8]) -> Self;
+    /// Converts the values of the typed field of a `TensorProto`, which are either
+    /// varints or the bits of fixed size values.
+    fn from_proto_vals(vals: &[u64]) -> Self;
+}
+
+fn put_le(buf: &mut Vec<u8>, val: u64, size: usize) {
+    for i in 0..size {
+        buf.push((val >> (8 * i)) as u8);
+    }
+}
+
+fn get_le(bytes: &[u8]) -> u64 {
+    bytes.iter().enumerate().fold(0, |val, (i, b)| val | u64::from(*b) << (8 * i))
+}
+
+macro_rules! impl_fixed {
+    (
+        $type:ty, size: $size:expr, dtype: $dtype:expr, field: $field:expr, wire: $wire:ident,
+        vals: $vals:expr, descr: $descr:expr,
+        write($s:ident, $buf:ident) $write:block
+        read($bytes:ident) $read:block
+        from_vals($v:ident) $from:block
+    ) => {
+        impl Fixed for $type {
+            const SIZE: usize = $size;
+            const PROTO_DTYPE: u64 = $dtype;
+            const PROTO_FIELD: u32 = $field;
+            const PROTO_WIRE: Wire = Wire::$wire;
+            const PROTO_VALS: usize = $vals;
+            const NPY_DESCR: &'static str = $descr;
+
+            fn write_le(&self, $buf: &mut Vec<u8>) {
+                let $s = self;
+                $write
+            }
+
+            fn read_le($bytes: &[u8]) -> $type {
+                $read
+            }
+
+            fn from_proto_vals($v: &[u64]) -> $type {
+                $from
+            }
+        }
+    };
+}
+
+impl_fixed!(f32, size: 4, dtype: 1, field: 5, wire: Fixed32, vals: 1, descr: "'<f4'",
+    write(s, buf) { put_le(buf, u64::from(s.to_bits()), 4) }
+    read(b) { f32::from_bits(get_le(b) as u32) }
+    from_vals(v) { f32::from_bits(v[0] as u32) }
+);
+impl_fixed!(f64, size: 8, dtype: 2, field: 6, wire: Fixed64, vals: 1, descr: "'<f8'",
+    write(s, buf) { put_le(buf, s.to_bits(), 8) }
+    read(b) { f64::from_bits(get_le(b)) }
+    from_vals(v) { f64::from_bits(v[0]) }
+);
+impl_fixed!(i32, size: 4, dtype: 3, field: 7, wire: Varint, vals: 1, descr: "'<i4'",
+    write(s, buf) { put_le(buf, u64::from(*s as u32), 4) }
+    read(b) { get_le(b) as u32 as i32 }
+    from_vals(v) { v[0] as i32 }
+);
+impl_fixed!(u8, size: 1, dtype: 4, field: 7, wire: Varint, vals: 1, descr: "'|u1'",
+    write(s, buf) { buf.push(*s) }
+    read(b) { b[0] }
+    from_vals(v) { v[0] as u8 }
+);
+impl_fixed!(i16, size: 2, dtype: 5, field: 7, wire: Varint, vals: 1, descr: "'<i2'",
+    write(s, buf) { put_le(buf, u64::from(*s as u16), 2) }
+    read(b) { get_le(b) as u16 as i16 }
+    from_vals(v) { v[0] as i16 }
+);
+impl_fixed!(i8, size: 1, dtype: 6, field: 7, wire: Varint, vals: 1, descr: "'|i1'",
+    write(s, buf) { buf.push(*s as u8) }
+    read(b) { b[0] as i8 }
+    from_vals(v) { v[0] as i8 }
+);
+impl_fixed!(i64, size: 8, dtype: 9, field: 10, wire: Varint, vals: 1, descr: "'<i8'",
+    write(s, buf) { put_le(buf, *s as u64, 8) }
+    read(b) { get_le(b) as i64 }
+    from_vals(v) { v[0] as i64 }
+);
+impl_fixed!(bool, size: 1, dtype: 10, field: 11, wire: Varint, vals: 1, descr: "'|b1'",
+    write(s, buf) { buf.push(*s as u8) }
+    read(b) { b[0] != 0 }
+    from_vals(v) { v[0] != 0 }
+);
+impl_fixed!(::QUInt8, size: 1, dtype: 12, field: 7, wire: Varint, vals: 1,
+    descr: "[('quint8', '|u1')]",
+    write(s, buf) { buf.push((*s).into()) }
+    read(b) { ::QUInt8::from(b[0]) }
+    from_vals(v) { ::QUInt8::from(v[0] as u8) }
+);
+impl_fixed!(::QInt32, size: 4, dtype: 13, field: 7, wire: Varint, vals: 1,
+    descr: "[('qint32', '<i4')]",
+    write(s, buf) { let val: i32 = (*s).into(); put_le(buf, u64::from(val as u32), 4) }
+    read(b) { ::QInt32::from(get_le(b) as u32 as i32) }
+    from_vals(v) { ::QInt32::from(v[0] as i32) }
+);
+impl_fixed!(::BFloat16, size: 2, dtype: 14, field: 13, wire: Varint, vals: 1,
+    descr: "[('bfloat16', '<u2')]",
+    write(s, buf) { let val: f32 = (*s).into(); put_le(buf, u64::from(val.to_bits() >> 16), 2) }
+    read(b) { ::BFloat16::from(f32::from_bits((get_le(b) as u32) << 16)) }
+    from_vals(v) { ::BFloat16::from(f32::from_bits((v[0] as u32) << 16)) }
+);
+impl_fixed!(::QInt16, size: 2, dtype: 15, field: 7, wire: Varint, vals: 1,
+    descr: "[('qint16', '<i2')]",
+    write(s, buf) { let val: i16 = (*s).into(); put_le(buf, u64::from(val as u16), 2) }
+    read(b) { ::QInt16::from(get_le(b) as u16 as i16) }
+    from_vals(v) { ::QInt16::from(v[0] as i16) }
+);
+impl_fixed!(::QUInt16, size: 2, dtype: 16, field: 7, wire: Varint, vals: 1,
+    descr: "[('quint16', '<u2')]",
+    write(s, buf) { let val: u16 = (*s).into(); put_le(buf, u64::from(val), 2) }
+    read(b) { ::QUInt16::from(get_le(b) as u16) }
+    from_vals(v) { ::QUInt16::from(v[0] as u16) }
+);
+impl_fixed!(::Complex32, size: 8, dtype: 8, field: 9, wire: Fixed32, vals: 2, descr: "'<c8'",
+    write(s, buf) {
+        put_le(buf, u64::from(s.re.to_bits()), 4);
+        put_le(buf, u64::from(s.im.to_bits()), 4);
+    }
+    read(b) {
+        let re = f32::from_bits(get_le(&b[..4]) as u32);
+        ::Complex32::new(re, f32::from_bits(get_le(&b[4..]) as u32))
+    }
+    from_vals(v) { ::Complex32::new(f32::from_bits(v[0] as u32), f32::from_bits(v[1] as u32)) }
+);
+impl_fixed!(::Complex64, size: 16, dtype: 18, field: 12, wire: Fixed64, vals: 2, descr: "'<c16'",
+    write(s, buf) {
+        put_le(buf, s.re.to_bits(), 8);
+        put_le(buf, s.im.to_bits(), 8);
+    }
+    read(b) { ::Complex64::new(f64::from_bits(get_le(&b[..8])), f64::from_bits(get_le(&b[8..]))) }
+    from_vals(v) { ::Complex64::new(f64::from_bits(v[0]), f64::from_bits(v[1])) }
+);
+
+/// Element types of the tensors which can be serialized.
+trait Element: FromTensorContent {
+    const PROTO_DTYPE: u64;
+
+    fn write_proto_values(values: &[Self], proto: &mut ProtoWriter);
+    /// Reads the values from the `tensor_content` or the typed fields of a `TensorProto`.
+    fn read_proto_values(content: &[u8], fields: &[WireValue]) -> Result<Vec<Self>>;
+    fn npy_descr(values: &[Self]) -> String;
+    fn write_npy_values(values: &[Self], buf: &mut Vec<u8>);
+    fn read_npy_values(descr: &str, data: &[u8], len: usize) -> Result<Vec<Self>>;
+}
+
+impl<T: Fixed + FromTensorContent> Element for T {
+    const PROTO_DTYPE: u64 = <T as Fixed>::PROTO_DTYPE;
+
+    fn write_proto_values(values: &[T], proto: &mut ProtoWriter) {
+        let mut content = Vec::with_capacity(values.len() * T::SIZE);
+        for val in values {
+            val.write_le(&mut content);
+        }
+        proto.bytes(4, &content);
+    }
+
+    fn read_proto_values(content: &[u8], fields: &[WireValue]) -> Result<Vec<T>> {
+        if !content.is_empty() {
+            if content.len() % T::SIZE != 0 {
+                return Err(invalid_tensor_proto("invalid tensor content size"));
+            }
+            return Ok(content.chunks(T::SIZE).map(T::read_le).collect());
+        }
+        let mut vals = vec![];
+        for field in fields {
+            match *field {
+                WireValue::LengthDelimited(packed) => match T::PROTO_WIRE {
+                    Wire::Varint => vals.extend(read_packed_varints(packed)?),
+                    Wire::Fixed32 | Wire::Fixed64 => {
+                        let size = if let Wire::Fixed32 = T::PROTO_WIRE { 4 } else { 8 };
+                        if packed.len() % size != 0 {
+                            return Err(invalid_tensor_proto("invalid packed field size"));
+                        }
+                        vals.extend(packed.chunks(size).map(get_le));
+                    }
+                },
+                WireValue::Varint(val) | WireValue::Fixed64(val) => vals.push(val),
+                WireValue::Fixed32(val) => vals.push(u64::from(val)),
+            }
+        }
+        if vals.len() % T::PROTO_VALS != 0 {
+            return Err(invalid_tensor_proto("incomplete complex value"));
+        }
+        Ok(vals.chunks(T::PROTO_VALS).map(T::from_proto_vals).collect())
+    }
+
+    fn npy_descr(_values: &[T]) -> String {
+        T::NPY_DESCR.to_owned()
+    }
+
+    fn write_npy_values(values: &[T], buf: &mut Vec<u8>) {
+        for val in values {
+            val.write_le(buf);
+        }
+    }
+
+    fn read_npy_values(_descr: &str, data: &[u8], len: usize) -> Result<Vec<T>> {
+        let size = len.checked_mul(T::SIZE).ok_or_else(|| invalid_npy("the array is too large"))?;
+        if data.len() < size {
+            return Err(invalid_npy("unexpected end of data"));
+        }
+        Ok(data[..size].chunks(T::SIZE).map(T::read_le).collect())
+    }
+}
+
+impl Element for String {
+    const PROTO_DTYPE: u64 = 7;
+
+    fn write_proto_values(values: &[String], proto: &mut ProtoWriter) {
+        for val in values {
+            proto.string(8, val);
+        }
+    }
+
+    fn read_proto_values(_content: &[u8], fields: &[WireValue]) -> Result<Vec<String>> {
+        fields.iter().map(|x| x.as_str().map(|s| s.to_owned())).collect()
+    }
+
+    /// Strings are stored as null padded byte strings of the length of the longest one,
+    /// so as in NumPy trailing null characters are lost.
+    fn npy_descr(values: &[String]) -> String {
+        let len = values.iter().map(|x| x.len()).max().unwrap_or(0);
+        format!("'|S{}'", ::std::cmp::max(len, 1))
+    }
+
+    fn write_npy_values(values: &[String], buf: &mut Vec<u8>) {
+        let len = values.iter().map(|x| x.len()).max().unwrap_or(0);
+        for val in values {
+            buf.extend_from_slice(val.as_bytes());
+            for _ in val.len()..::std::cmp::max(len, 1) {
+                buf.push(0);
+            }
+        }
+    }
+
+    /// Reads byte strings (`S`) and unicode strings (`U`), the trailing null characters
+    /// (the padding) are stripped as NumPy does.
+    fn read_npy_values(descr: &str, data: &[u8], len: usize) -> Result<Vec<String>> {
+        let unicode = descr.starts_with("'<U");
+        let invalid_type = || invalid_npy(&format!("invalid string type {}", descr));
+        let chars: usize = descr.trim_matches('\'')[2..].parse().map_err(|_| invalid_type())?;
+        let size = if unicode { chars.checked_mul(4).ok_or_else(invalid_type)? } else { chars };
+        if size == 0 {
+            return Err(invalid_type());
+        }
+        let total = len.checked_mul(size).ok_or_else(|| invalid_npy("the array is too large"))?;
+        if data.len() < total {
+            return Err(invalid_npy("unexpected end of data"));
+        }
+        let mut values = Vec::with_capacity(len);
+        for item in data[..total].chunks(size) {
+            let val = if unicode {
+                let mut chars: Vec<u32> = item.chunks(4).map(|c| get_le(c) as u32).collect();
+                while chars.last() == Some(&0) {
+                    chars.pop();
+                }
+                chars
+                    .into_iter()
+                    .map(|c| {
+                        ::std::char::from_u32(c).ok_or_else(|| invalid_npy("invalid char"))
+                    })
+                    .collect::<Result<String>>()?
+            } else {
+                let end = item.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+                String::from_utf8(item[..end].to_vec())
+                    .map_err(|_| invalid_npy("invalid utf-8 string"))?
+            };
+            values.push(val);
+        }
+        Ok(values)
+    }
+}
+
+/// Builds a tensor from its values, if there are less values than elements the last
+/// one is repeated (as in a `TensorProto`).
+fn build_tensor<T: TensorType>(dims: &[u64], values: Vec<T>) -> Result<TypedTensor<T>> {
+    let mut tensor = TypedTensor::new(dims);
+    if values.len() > tensor.len() {
+        return Err(Error::from(ErrorKind::InvalidArgument(format!(
+            "found {} values for a tensor with {} elements",
+            values.len(),
+            tensor.len()
+        ))));
+    }
+    if let Some(last) = values.last() {
+        tensor[..values.len()].clone_from_slice(&values);
+        for x in tensor[values.len()..].iter_mut() {
+            *x = last.clone();
+        }
+    }
+    Ok(tensor)
+}
+
+///// TensorProto /////
+
+fn invalid_tensor_proto(msg: &str) -> Error {
+    Error::from(ErrorKind::InvalidArgument(format!("TensorProto: {}", msg)))
+}
+
+fn write_proto<T: Element>(tensor: &TypedTensor<T>) -> Vec<u8> {
+    let mut shape = ProtoWriter::new();
+    for dim in tensor.dims() {
+        let mut size = ProtoWriter::new();
+        size.int64(1, *dim as i64);
+        shape.bytes(2, &size.into_bytes());
+    }
+    let mut proto = ProtoWriter::new();
+    proto.varint(1, T::PROTO_DTYPE).bytes(2, &shape.into_bytes());
+    T::write_proto_values(tensor, &mut proto);
+    proto.into_bytes()
+}
+
+fn read_proto_shape(buf: &[u8]) -> Result<Vec<u64>> {
+    let mut dims = vec![];
+    let mut reader = ProtoReader::new(buf);
+    while let Some((field, val)) = reader.next_field()? {
+        match field {
+            2 => {
+                let mut size = 0;
+                let mut dim = ProtoReader::new(val.as_bytes()?);
+                while let Some((field, val)) = dim.next_field()? {
+                    if field == 1 {
+                        size = val.as_varint()? as i64;
+                    }
+                }
+                if size < 0 {
+                    return Err(invalid_tensor_proto("the shape is not fully defined"));
+                }
+                dims.push(size as u64);
+            }
+            3 if val.as_varint()? != 0 => {
+                return Err(invalid_tensor_proto("the shape is not fully defined"));
+            }
+            _ => {}
+        }
+    }
+    Ok(dims)
+}
+
+fn read_proto<T: Element>(
+    dims: &[u64],
+    content: &[u8],
+    fields: &[(u32, WireValue)],
+    field: u32,
+) -> Result<TypedTensor<T>> {
+    let fields: Vec<_> = fields.iter().filter(|x| x.0 == field).map(|x| x.1).collect();
+    build_tensor(dims, T::read_proto_values(content, &fields)?)
+}
+
+///// NumPy /////
+
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+
+fn write_npy<T: Element>(tensor: &TypedTensor<T>) -> Vec<u8> {
+    let shape = match tensor.dims().len() {
+        0 => "()".to_owned(),
+        1 => format!("({},)", tensor.dims()[0]),
+        _ => {
+            let dims: Vec<_> = tensor.dims().iter().map(|x| x.to_string()).collect();
+            format!("({})", dims.join(", "))
+        }
+    };
+    let mut header = format!(
+        "{{'descr': {}, 'fortran_order': False, 'shape': {}, }}",
+        T::npy_descr(tensor),
+        shape
+    );
+    // the data is aligned to 64 bytes, header ends with a newline
+    while (NPY_MAGIC.len() + 4 + header.len() + 1) % 64 != 0 {
+        header.push(' ');
+    }
+    header.push('\n');
+
+    let mut buf = NPY_MAGIC.to_vec();
+    buf.extend_from_slice(&[1, 0]);
+    put_le(&mut buf, header.len() as u64, 2);
+    buf.extend_from_slice(header.as_bytes());
+    T::write_npy_values(tensor, &mut buf);
+    buf
+}
+
+/// Returns the value of a key in the header dictionary of a `.npy` file.
+fn npy_header_value<'a>(header: &'a str, key: &str) -> Result<&'a str> {
+    let key = format!("'{}':", key);
+    let start = header
+        .find(&key)
+        .ok_or_else(|| invalid_npy(&format!("missing {} in header", key)))?;
+    let value = header[start + key.len()..].trim_left();
+    let end = match value.chars().next() {
+        Some('\'') => value[1..].find('\'').map(|x| x + 2),
+        Some('[') => value.find(']').map(|x| x + 1),
+        Some('(') => value.find(')').map(|x| x + 1),
+        _ => value.find(|c| c == ',' || c == '}'),
+    };
+    end.map(|end| &value[..end])
+        .ok_or_else(|| invalid_npy("invalid header"))
+}
+
+fn invalid_npy(msg: &str) -> Error {
+    Error::from(ErrorKind::InvalidArgument(format!("npy: {}", msg)))
+}
+
+fn read_npy(buf: &[u8]) -> Result<TensorContent> {
+    if buf.len() < 10 || &buf[..6] != NPY_MAGIC {
+        return Err(invalid_npy("invalid magic string"));
+    }
+    let (header_start, header_len) = match buf[6] {
+        1 => (10, get_le(&buf[8..10]) as usize),
+        2 | 3 if buf.len() >= 12 => (12, get_le(&buf[8..12]) as usize),
+        version => return Err(invalid_npy(&format!("unsupported version {}", version))),
+    };
+    let data_start = header_start + header_len;
+    if buf.len() < data_start {
+        return Err(invalid_npy("unexpected end of header"));
+    }
+    let header = ::std::str::from_utf8(&buf[header_start..data_start])
+        .map_err(|_| invalid_npy("invalid header"))?;
+
+    let descr: String = npy_header_value(header, "descr")?
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| if c == '"' { '\'' } else { c })
+        .collect();
+    if npy_header_value(header, "fortran_order")? != "False" {
+        return Err(invalid_npy("fortran order arrays are not supported"));
+    }
+    let shape = npy_header_value(header, "shape")?;
+    let invalid_shape = || invalid_npy(&format!("invalid shape {}", shape));
+    if shape.len() < 2 || !shape.starts_with('(') || !shape.ends_with(')') {
+        return Err(invalid_shape());
+    }
+    let mut dims = vec![];
+    for dim in shape[1..shape.len() - 1].split(',').map(|x| x.trim()) {
+        if !dim.is_empty() {
+            let dim = dim.trim_right_matches('L').parse().map_err(|_| invalid_shape())?;
+            dims.push(dim);
+        }
+    }
+    let len = dims.iter()
+        .try_fold(1_u64, |acc, dim| acc.checked_mul(*dim))
+        .filter(|len| *len <= ::std::usize::MAX as u64)
+        .ok_or_else(|| invalid_npy("the array is too large"))? as usize;
+    let data = &buf[data_start..];
+
+    macro_rules! read_as {
+        ($variant:ident, $type:ty) => {
+            TensorContent::$variant(
+                build_tensor(&dims, <$type as Element>::read_npy_values(&descr, data, len)?)?
+            )
+        }
+    }
+    let content = match descr.as_str() {
+        d if d == f32::NPY_DESCR => read_as!(Float, f32),
+        d if d == f64::NPY_DESCR => read_as!(Double, f64),
+        d if d == i32::NPY_DESCR => read_as!(Int32, i32),
+        d if d == u8::NPY_DESCR => read_as!(UInt8, u8),
+        d if d == i16::NPY_DESCR => read_as!(Int16, i16),
+        d if d == i8::NPY_DESCR => read_as!(Int8, i8),
+        d if d == i64::NPY_DESCR => read_as!(Int64, i64),
+        d if d == bool::NPY_DESCR => read_as!(Bool, bool),
+        d if d.starts_with("'|S") || d.starts_with("'<U") => read_as!(String, String),
+        d if d == <::QUInt8 as Fixed>::NPY_DESCR.replace(" ", "") => read_as!(QUInt8, ::QUInt8),
+        d if d == <::QUInt16 as Fixed>::NPY_DESCR.replace(" ", "") => {
+            read_as!(QUInt16, ::QUInt16)
+        }
+        d if d == <::QInt16 as Fixed>::NPY_DESCR.replace(" ", "") => read_as!(QInt16, ::QInt16),
+        d if d == <::QInt32 as Fixed>::NPY_DESCR.replace(" ", "") => read_as!(QInt32, ::QInt32),
+        d if d == <::BFloat16 as Fixed>::NPY_DESCR.replace(" ", "") => {
+            read_as!(BFloat16, ::BFloat16)
+        }
+        d if d == <::Complex32 as Fixed>::NPY_DESCR => read_as!(Complex64, ::Complex32),
+        d if d == <::Complex64 as Fixed>::NPY_DESCR => read_as!(Complex128, ::Complex64),
+        d => return Err(invalid_npy(&format!("unsupported data type {}", d))),
+    };
+    Ok(content)
+}
+
+/// Table-less CRC-32 (as used by zip archives).
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0_u32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Writes an uncompressed zip archive, as `numpy.savez` does.
+///
+/// The sizes, offsets and number of entries which don't fit in the fields of the zip
+/// headers are written in the zip64 extensions.
+fn write_zip(entries: &[(String, Vec<u8>)]) -> Vec<u8> {
+    const MAX_16: u64 = 0xFFFF;
+    const MAX_32: u64 = 0xFFFF_FFFF;
+    // zip64 extended information extra field
+    let extra_field = |values: &[u64]| {
+        let mut extra = vec![];
+        if !values.is_empty() {
+            put_le(&mut extra, 0x0001, 2);
+            put_le(&mut extra, 8 * values.len() as u64, 2);
+            for val in values {
+                put_le(&mut extra, *val, 8);
+            }
+        }
+        extra
+    };
+
+    let mut buf = vec![];
+    let mut central_dir = vec![];
+    for &(ref name, ref data) in entries {
+        let offset = buf.len() as u64;
+        let size = data.len() as u64;
+        let crc = crc32(data);
+        // the uncompressed and compressed sizes, and the offset for the central directory
+        let mut zip64 = vec![];
+        if size >= MAX_32 {
+            zip64.extend_from_slice(&[size, size]);
+        }
+        let local_extra = extra_field(&zip64);
+        if offset >= MAX_32 {
+            zip64.push(offset);
+        }
+        let central_extra = extra_field(&zip64);
+
+        // local file header
+        put_le(&mut buf, 0x0403_4b50, 4);
+        let version = if local_extra.is_empty() { 20 } else { 45 };
+        for val in &[version, 0, 0, 0, 0x21] {
+            put_le(&mut buf, *val, 2);
+        }
+        put_le(&mut buf, u64::from(crc), 4);
+        put_le(&mut buf, size.min(MAX_32), 4);
+        put_le(&mut buf, size.min(MAX_32), 4);
+        put_le(&mut buf, name.len() as u64, 2);
+        put_le(&mut buf, local_extra.len() as u64, 2);
+        buf.extend_from_slice(name.as_bytes());
+        buf.extend_from_slice(&local_extra);
+        buf.extend_from_slice(data);
+        // central directory header
+        put_le(&mut central_dir, 0x0201_4b50, 4);
+        let version = if central_extra.is_empty() { 20 } else { 45 };
+        for val in &[version, version, 0, 0, 0, 0x21] {
+            put_le(&mut central_dir, *val, 2);
+        }
+        put_le(&mut central_dir, u64::from(crc), 4);
+        put_le(&mut central_dir, size.min(MAX_32), 4);
+        put_le(&mut central_dir, size.min(MAX_32), 4);
+        put_le(&mut central_dir, name.len() as u64, 2);
+        put_le(&mut central_dir, central_extra.len() as u64, 2);
+        for _ in 0..3 {
+            put_le(&mut central_dir, 0, 2);
+        }
+        put_le(&mut central_dir, 0, 4);
+        put_le(&mut central_dir, offset.min(MAX_32), 4);
+        central_dir.extend_from_slice(name.as_bytes());
+        central_dir.extend_from_slice(&central_extra);
+    }
+    let num_entries = entries.len() as u64;
+    let central_dir_size = central_dir.len() as u64;
+    let central_dir_offset = buf.len() as u64;
+    buf.extend_from_slice(&central_dir);
+    if num_entries >= MAX_16 || central_dir_size >= MAX_32 || central_dir_offset >= MAX_32 {
+        // zip64 end of central directory record
+        let record = buf.len() as u64;
+        put_le(&mut buf, 0x0606_4b50, 4);
+        for &(val, size) in &[(44, 8), (45, 2), (45, 2), (0, 4), (0, 4)] {
+            put_le(&mut buf, val, size);
+        }
+        put_le(&mut buf, num_entries, 8);
+        put_le(&mut buf, num_entries, 8);
+        put_le(&mut buf, central_dir_size, 8);
+        put_le(&mut buf, central_dir_offset, 8);
+        // zip64 end of central directory locator
+        put_le(&mut buf, 0x0706_4b50, 4);
+        put_le(&mut buf, 0, 4);
+        put_le(&mut buf, record, 8);
+        put_le(&mut buf, 1, 4);
+    }
+    // end of central directory, the values which don't fit are in the zip64 record
+    put_le(&mut buf, 0x0605_4b50, 4);
+    put_le(&mut buf, 0, 2);
+    put_le(&mut buf, 0, 2);
+    put_le(&mut buf, num_entries.min(MAX_16), 2);
+    put_le(&mut buf, num_entries.min(MAX_16), 2);
+    put_le(&mut buf, central_dir_size.min(MAX_32), 4);
+    put_le(&mut buf, central_dir_offset.min(MAX_32), 4);
+    put_le(&mut buf, 0, 2);
+    buf
+}
+
+fn invalid_npz(msg: &str) -> Error {
+    Error::from(ErrorKind::InvalidArgument(format!("npz: {}", msg)))
+}
+
+/// Returns the data of the zip64 extended information in the extra field of a zip
+/// header, if any.
+fn zip64_extra(mut extra: &[u8]) -> Option<&[u8]> {
+    while extra.len() >= 4 {
+        let id = get_le(&extra[..2]);
+        let len = get_le(&extra[2..4]) as usize;
+        let data = extra.get(4..4 + len)?;
+        if id == 0x0001 {
+            return Some(data);
+        }
+        extra = &extra[4 + len..];
+    }
+    None
+}
+
+/// Reads the entries of an uncompressed zip archive.
+///
+/// The zip64 extensions are supported, `numpy.savez` writes every entry with them.
+fn read_zip(buf: &[u8]) -> Result<Vec<(String, &[u8])>> {
+    let invalid = || invalid_npz("invalid zip archive");
+    let slice = |start: usize, len: usize| {
+        start.checked_add(len).and_then(|end| buf.get(start..end)).ok_or_else(invalid)
+    };
+    let read = |start: usize, len: usize| slice(start, len).map(get_le);
+    let to_usize = |val: u64| {
+        if val <= ::std::usize::MAX as u64 {
+            Ok(val as usize)
+        } else {
+            Err(invalid())
+        }
+    };
+
+    let eocd = (0..buf.len().saturating_sub(21))
+        .rev()
+        .find(|i| get_le(&buf[*i..*i + 4]) == 0x0605_4b50)
+        .ok_or_else(invalid)?;
+    let mut num_entries = read(eocd + 10, 2)?;
+    let mut central_dir = read(eocd + 16, 4)?;
+    if num_entries == 0xFFFF || central_dir == 0xFFFF_FFFF {
+        // the zip64 end of central directory locator precedes the end of central
+        // directory, and points to the zip64 end of central directory record
+        let locator = eocd.checked_sub(20).ok_or_else(invalid)?;
+        if read(locator, 4)? != 0x0706_4b50 {
+            return Err(invalid());
+        }
+        let record = to_usize(read(locator + 8, 8)?)?;
+        if read(record, 4)? != 0x0606_4b50 {
+            return Err(invalid());
+        }
+        num_entries = read(record + 32, 8)?;
+        central_dir = read(record + 48, 8)?;
+    }
+
+    let mut pos = to_usize(central_dir)?;
+    let mut entries = vec![];
+    for _ in 0..num_entries {
+        let header = slice(pos, 46)?;
+        if get_le(&header[..4]) != 0x0201_4b50 {
+            return Err(invalid());
+        }
+        let method = get_le(&header[10..12]);
+        let crc = get_le(&header[16..20]) as u32;
+        let name_len = get_le(&header[28..30]) as usize;
+        let extra_len = get_le(&header[30..32]) as usize;
+        let comment_len = get_le(&header[32..34]) as usize;
+        // uncompressed size, compressed size and offset of the local header
+        let mut fields = [
+            get_le(&header[24..28]),
+            get_le(&header[20..24]),
+            get_le(&header[42..46]),
+        ];
+        let name = String::from_utf8(slice(pos + 46, name_len)?.to_vec()).map_err(|_| invalid())?;
+        if let Some(zip64) = zip64_extra(slice(pos + 46 + name_len, extra_len)?) {
+            // only the fields which don't fit in the header are present, in order
+            let mut values = zip64.chunks(8).filter(|x| x.len() == 8).map(get_le);
+            for field in fields.iter_mut().filter(|x| **x == 0xFFFF_FFFF) {
+                *field = values.next().ok_or_else(invalid)?;
+            }
+        }
+        pos += 46 + name_len + extra_len + comment_len;
+        if method != 0 {
+            return Err(invalid_npz(&format!(
+                "entry `{}` is compressed, only uncompressed archives are supported",
+                name
+            )));
+        }
+
+        let offset = to_usize(fields[2])?;
+        let local = slice(offset, 30)?;
+        let data_start = offset + 30 + get_le(&local[26..28]) as usize
+            + get_le(&local[28..30]) as usize;
+        let data = slice(data_start, to_usize(fields[1])?)?;
+        if crc32(data) != crc {
+            return Err(invalid_npz(&format!("entry `{}` is corrupted", name)));
+        }
+        entries.push((name, data));
+    }
+    Ok(entries)
+}
+
+fn read_file<P: AsRef<Path>>(path: P) -> Result<Vec<u8>> {
+    let mut buf = vec![];
+    File::open(path)?.read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+fn write_file<P: AsRef<Path>>(path: P, buf: &[u8]) -> Result<()> {
+    File::create(path)?.write_all(buf)?;
+    Ok(())
+}
+
+impl TensorContent {
+    /// Serializes the tensor as a `TensorProto` message.
+    pub fn to_tensor_proto(&self) -> Vec<u8> {
+        tensor_output_op!(self; write_proto[])
+    }
+
+    /// Deserializes a tensor from a `TensorProto` message.
+    pub fn from_tensor_proto(buf: &[u8]) -> Result<TensorContent> {
+        let mut dtype = 0;
+        let mut dims = vec![];
+        let mut content: &[u8] = &[];
+        let mut fields = vec![];
+        let mut reader = ProtoReader::new(buf);
+        while let Some((field, val)) = reader.next_field()? {
+            match field {
+                1 => dtype = val.as_varint()?,
+                2 => dims = read_proto_shape(val.as_bytes()?)?,
+                4 => content = val.as_bytes()?,
+                5..=13 => fields.push((field, val)),
+                _ => {}
+            }
+        }
+
+        macro_rules! read_as {
+            ($variant:ident, $type:ty) => {
+                TensorContent::$variant(read_proto::<$type>(
+                    &dims,
+                    content,
+                    &fields,
+                    <$type as Fixed>::PROTO_FIELD,
+                )?)
+            }
+        }
+        let content = match dtype {
+            1 => read_as!(Float, f32),
+            2 => read_as!(Double, f64),
+            3 => read_as!(Int32, i32),
+            4 => read_as!(UInt8, u8),
+            5 => read_as!(Int16, i16),
+            6 => read_as!(Int8, i8),
+            7 => TensorContent::String(read_proto::<String>(&dims, content, &fields, 8)?),
+            8 => read_as!(Complex64, ::Complex32),
+            9 => read_as!(Int64, i64),
+            10 => read_as!(Bool, bool),
+            12 => read_as!(QUInt8, ::QUInt8),
+            13 => read_as!(QInt32, ::QInt32),
+            14 => read_as!(BFloat16, ::BFloat16),
+            15 => read_as!(QInt16, ::QInt16),
+            16 => read_as!(QUInt16, ::QUInt16),
+            18 => read_as!(Complex128, ::Complex64),
+            _ => return Err(invalid_tensor_proto(&format!("unsupported data type {}", dtype))),
+        };
+        Ok(content)
+    }
+
+    /// Serializes the tensor in the `.npy` format.
+    ///
+    /// Strings are stored as byte strings, and the quantized and `BFloat16` types as
+    /// structured types with a single field named after the type (as TensorFlow does).
+    /// As with NumPy byte strings, the strings are padded with null characters, so the
+    /// trailing ones are lost when reading them back.
+    pub fn to_npy(&self) -> Vec<u8> {
+        tensor_output_op!(self; write_npy[])
+    }
+
+    /// Deserializes a tensor in the `.npy` format.
+    pub fn from_npy(buf: &[u8]) -> Result<TensorContent> {
+        read_npy(buf)
+    }
+
+    /// Serializes the tensors as the arrays of a `.npz` archive, with the given names.
+    pub fn to_npz<S: AsRef<str>>(tensors: &[(S, &TensorContent)]) -> Vec<u8> {
+        let entries: Vec<_> = tensors
+            .iter()
+            .map(|&(ref name, tensor)| (format!("{}.npy", name.as_ref()), tensor.to_npy()))
+            .collect();
+        write_zip(&entries)
+    }
+
+    /// Deserializes the arrays of a `.npz` archive, along with their names.
+    ///
+    /// Only uncompressed archives (as written by `numpy.savez`) are supported.
+    pub fn from_npz(buf: &[u8]) -> Result<Vec<(String, TensorContent)>> {
+        let mut tensors = vec![];
+        for (name, data) in read_zip(buf)? {
+            let name = if name.ends_with(".npy") {
+                name[..name.len() - 4].to_owned()
+            } else {
+                name
+            };
+            tensors.push((name, read_npy(data)?));
+        }
+        Ok(tensors)
+    }
+
+    /// Saves the tensor to a `.npy` file.
+    pub fn save_npy<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        write_file(path, &self.to_npy())
+    }
+
+    /// Loads a tensor from a `.npy` file.
+    pub fn load_npy<P: AsRef<Path>>(path: P) -> Result<TensorContent> {
+        read_npy(&read_file(path)?)
+    }
+
+    /// Saves the tensors to a `.npz` file.
+    pub fn save_npz<P, S>(path: P, tensors: &[(S, &TensorContent)]) -> Result<()>
+    where
+        P: AsRef<Path>,
+        S: AsRef<str>,
+    {
+        write_file(path, &TensorContent::to_npz(tensors))
+    }
+
+    /// Loads the tensors from a `.npz` file.
+    pub fn load_npz<P: AsRef<Path>>(path: P) -> Result<Vec<(String, TensorContent)>> {
+        TensorContent::from_npz(&read_file(path)?)
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn test_round_trip() {
+    fn tensor<T: TensorType>(values: &[T]) -> TypedTensor<T> {
+        let mut tensor = TypedTensor::new(&[2, values.len() as u64 / 2]);
+        tensor.clone_from_slice(values);
+        tensor
+    }
+
+    let tensors = vec![
+        TensorContent::from(tensor(&[true, false])),
+        TensorContent::from(tensor(&[1.5_f32, -2.])),
+        TensorContent::from(tensor(&[1.5_f64, -2.])),
+        TensorContent::from(tensor(&[1_u8, 255])),
+        TensorContent::from(tensor(&[1_i8, -128])),
+        TensorContent::from(tensor(&[1_i16, -300])),
+        TensorContent::from(tensor(&[1_i32, -70_000])),
+        TensorContent::from(tensor(&[1_i64, -5_000_000_000])),
+        TensorContent::from(tensor(&["a".to_owned(), "bcd".to_owned()])),
+        TensorContent::from(tensor(&[::QUInt8::from(1), ::QUInt8::from(255)])),
+        TensorContent::from(tensor(&[::QUInt16::from(1), ::QUInt16::from(65_535)])),
+        TensorContent::from(tensor(&[::QInt16::from(1), ::QInt16::from(-300)])),
+        TensorContent::from(tensor(&[::QInt32::from(1), ::QInt32::from(-70_000)])),
+        TensorContent::from(tensor(&[::BFloat16::from(1.5), ::BFloat16::from(-2.)])),
+        TensorContent::from(tensor(&[::Complex32::new(1., -1.), ::Complex32::new(0.5, 2.)])),
+        TensorContent::from(tensor(&[::Complex64::new(1., -1.), ::Complex64::new(0.5, 2.)])),
+    ];
+
+    let same = |a: &TensorContent, b: &TensorContent| {
+        a.get_datatype() == b.get_datatype() && a.get_dims() == b.get_dims()
+            && format!("{:?}", a) == format!("{:?}", b)
+    };
+    for tensor in &tensors {
+        let proto = TensorContent::from_tensor_proto(&tensor.to_tensor_proto()).unwrap();
+        assert!(same(tensor, &proto), "{:?} != {:?}", tensor, proto);
+        let npy = TensorContent::from_npy(&tensor.to_npy()).unwrap();
+        assert!(same(tensor, &npy), "{:?} != {:?}", tensor, npy);
+    }
+
+    let named: Vec<_> = tensors.iter().enumerate().map(|(i, x)| (format!("t{}", i), x)).collect();
+    let npz = TensorContent::from_npz(&TensorContent::to_npz(&named)).unwrap();
+    assert_eq!(npz.len(), tensors.len());
+    for (&(ref name, ref tensor), &(ref orig_name, orig)) in npz.iter().zip(&named) {
+        assert_eq!(name, orig_name);
+        assert!(same(tensor, orig));
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn test_tensor_proto_typed_fields() {
+    // float_val with a single value is broadcasted to the whole shape
+    let mut dim = ProtoWriter::new();
+    dim.int64(1, 3);
+    let mut shape = ProtoWriter::new();
+    shape.bytes(2, &dim.into_bytes());
+    let mut proto = ProtoWriter::new();
+    let mut value = vec![];
+    put_le(&mut value, u64::from(2.5_f32.to_bits()), 4);
+    proto.varint(1, 1).bytes(2, &shape.into_bytes()).bytes(5, &value);
+
+    match TensorContent::from_tensor_proto(&proto.into_bytes()).unwrap() {
+        TensorContent::Float(ref val) => assert_eq!(&val[..], &[2.5, 2.5, 2.5]),
+        _ => panic!("wrong type"),
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn test_numpy_fixtures() {
+    // written by tests/fixtures/make_npy.py
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/npy");
+    match TensorContent::load_npy(dir.join("floats.npy")).unwrap() {
+        TensorContent::Float(ref val) => {
+            assert_eq!(val.dims(), &[2, 3]);
+            assert_eq!(&val[..], &[0., 0.5, 1., 1.5, 2., 2.5]);
+        }
+        _ => panic!("wrong type"),
+    }
+
+    let arrays = TensorContent::load_npz(dir.join("arrays.npz")).unwrap();
+    let names: Vec<_> = arrays.iter().map(|x| x.0.as_str()).collect();
+    assert_eq!(names, vec!["x", "y", "s"]);
+    match arrays[0].1 {
+        TensorContent::Int64(ref val) => assert_eq!(&val[..], &[1, 2, 3]),
+        _ => panic!("wrong type"),
+    }
+    match arrays[1].1 {
+        TensorContent::Double(ref val) => {
+            assert!(val.dims().is_empty());
+            assert_eq!(&val[..], &[1.5]);
+        }
+        _ => panic!("wrong type"),
+    }
+    match arrays[2].1 {
+        TensorContent::String(ref val) => assert_eq!(&val[..], &["ab", "cde"]),
+        _ => panic!("wrong type"),
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn test_invalid_npy() {
+    let npy = |header: &str| {
+        let mut buf = NPY_MAGIC.to_vec();
+        buf.extend_from_slice(&[1, 0]);
+        put_le(&mut buf, header.len() as u64, 2);
+        buf.extend_from_slice(header.as_bytes());
+        buf
+    };
+    for shape in &["3", "", "(", "(4294967296, 4294967296, 2)", "(1000000000000,)"] {
+        let header = format!(
+            "{{'descr': '<f4', 'fortran_order': False, 'shape': {}, }}\n",
+            shape
+        );
+        match *TensorContent::from_npy(&npy(&header)).unwrap_err().kind() {
+            ErrorKind::InvalidArgument(_) => {}
+            ref err => panic!("unexpected error for shape {}: {}", shape, err),
+        }
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn test_read_zip64() {
+    let data = TensorContent::from(TypedTensor::<i32>::new(&[2])).to_npy();
+    let crc = u64::from(crc32(&data));
+    let name = b"x.npy";
+    let mut buf = vec![];
+    // local header, the sizes are in the zip64 extra field
+    put_le(&mut buf, 0x0403_4b50, 4);
+    for val in &[45, 0, 0, 0, 0] {
+        put_le(&mut buf, *val, 2);
+    }
+    put_le(&mut buf, crc, 4);
+    put_le(&mut buf, 0xFFFF_FFFF, 4);
+    put_le(&mut buf, 0xFFFF_FFFF, 4);
+    put_le(&mut buf, name.len() as u64, 2);
+    put_le(&mut buf, 20, 2);
+    buf.extend_from_slice(name);
+    for &(val, size) in &[(1, 2), (16, 2), (data.len() as u64, 8), (data.len() as u64, 8)] {
+        put_le(&mut buf, val, size);
+    }
+    buf.extend_from_slice(&data);
+
+    // central directory header, the sizes and the offset are in the zip64 extra field
+    let central_dir = buf.len();
+    put_le(&mut buf, 0x0201_4b50, 4);
+    for val in &[45, 45, 0, 0, 0, 0] {
+        put_le(&mut buf, *val, 2);
+    }
+    put_le(&mut buf, crc, 4);
+    put_le(&mut buf, 0xFFFF_FFFF, 4);
+    put_le(&mut buf, 0xFFFF_FFFF, 4);
+    put_le(&mut buf, name.len() as u64, 2);
+    put_le(&mut buf, 28, 2);
+    for _ in 0..3 {
+        put_le(&mut buf, 0, 2);
+    }
+    put_le(&mut buf, 0, 4);
+    put_le(&mut buf, 0xFFFF_FFFF, 4);
+    buf.extend_from_slice(name);
+    for &(val, size) in &[(1, 2), (24, 2), (data.len() as u64, 8), (data.len() as u64, 8), (0, 8)] {
+        put_le(&mut buf, val, size);
+    }
+    let central_dir_len = buf.len() - central_dir;
+
+    // zip64 end of central directory record and locator, and end of central directory
+    let record = buf.len();
+    put_le(&mut buf, 0x0606_4b50, 4);
+    for &(val, size) in &[(44, 8), (45, 2), (45, 2), (0, 4), (0, 4), (1, 8), (1, 8)] {
+        put_le(&mut buf, val, size);
+    }
+    put_le(&mut buf, central_dir_len as u64, 8);
+    put_le(&mut buf, central_dir as u64, 8);
+    put_le(&mut buf, 0x0706_4b50, 4);
+    put_le(&mut buf, 0, 4);
+    put_le(&mut buf, record as u64, 8);
+    put_le(&mut buf, 1, 4);
+    put_le(&mut buf, 0x0605_4b50, 4);
+    for &(val, size) in &[(0, 2), (0, 2), (0xFFFF, 2), (0xFFFF, 2), (0xFFFF_FFFF, 4)] {
+        put_le(&mut buf, val, size);
+    }
+    put_le(&mut buf, 0xFFFF_FFFF, 4);
+    put_le(&mut buf, 0, 2);
+
+    let npz = TensorContent::from_npz(&buf).unwrap();
+    assert_eq!(npz.len(), 1);
+    assert_eq!(npz[0].0, "x");
+    assert_eq!(npz[0].1.get_dims(), &[2]);
+}
+
+#[test]
+#[cfg(test)]
+fn test_write_zip64() {
+    // more entries than fit in the end of central directory record
+    let entries: Vec<_> = (0..0x1_0000).map(|i| (format!("{}", i), vec![i as u8])).collect();
+    let buf = write_zip(&entries);
+    let read = read_zip(&buf).unwrap();
+    assert_eq!(read.len(), entries.len());
+    for (&(ref name, data), &(ref orig_name, ref orig)) in read.iter().zip(&entries) {
+        assert_eq!(name, orig_name);
+        assert_eq!(data, &orig[..]);
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn test_npy_strings_with_nulls() {
+    let mut tensor = TypedTensor::<String>::new(&[3]);
+    tensor.clone_from_slice(&["a\0b".to_owned(), "c\0".to_owned(), "\0".to_owned()]);
+    // as in NumPy, only the trailing null characters (the padding) are stripped
+    match TensorContent::from_npy(&TensorContent::from(tensor).to_npy()).unwrap() {
+        TensorContent::String(ref val) => assert_eq!(&val[..], &["a\0b", "c", ""]),
+        _ => panic!("wrong type"),
+    }
+}
@@ -7,6 +7,8 @@ extern crate num_complex;
 extern crate uuid;
 #[macro_use]
 extern crate error_chain;
+#[cfg(feature = "ndarray")]
+extern crate ndarray;
 
 #[macro_use]
 mod macros;
@@ -25,11 +27,14 @@ use tf::{QUInt8, QInt16, QUInt16, QInt32, BFloat16};
 use num_complex::{Complex32, Complex64};
 
 pub mod prelude {
-    pub use super::framework::{Attribute, Constant, DefinedShape, NodeIdent, Operation, Scope,
-                               Tensor, TensorArray, TensorContent, Variable};
-    pub use super::client::ClientSession;
+    pub use super::framework::{Attribute, Constant, DefinedShape, FromTensorContent, NodeIdent,
+                               Operation, PrintOptions, Scope, Tensor, TensorArray, TensorContent,
+                               Variable};
+    #[cfg(feature = "ndarray")]
+    pub use super::framework::{FromArray, ToArray};
+    pub use super::client::{ClientSession, SavedModelBundle};
     pub use super::{OperationData, TypedTensor};
-    pub use super::errors::Error as TFError;
+    pub use super::errors::{Error as TFError, ErrorKind as TFErrorKind};
     pub use tf::{DataType, Status};
 
     pub use super::train;
@@ -3,6 +3,68 @@
 use tf::Shape as TensorShape;
 
 use super::*;
+use framework::IntoShape;
+
+///// BroadcastGradientArgs /////
+
+/// Return the reduction indices for computing gradients of `s0 op s1` with broadcast.
+///
+/// This is typically used by gradient computations for a broadcasting operation.
+///
+/// ### Args
+/// * s0: A `Tensor`. Must be one of the following types: int32, int64.
+/// * s1: A `Tensor`. Must have the same type as `s0`.
+/// * name: A name for the operation (optional).
+///
+/// ### Returns
+/// * A tuple of `Tensor` objects `(r0, r1)`, the axes to reduce for each of the inputs.
+pub(crate) fn broadcast_gradient_args<Tx, Ty, S>(
+    context: &mut Scope,
+    s0: Tx,
+    s1: Ty,
+    name: S,
+) -> Result<(Tensor, Tensor)>
+where
+    Tx: Into<Tensor>,
+    Ty: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    context.install(BroadcastGradientArgs::new(s0.into(), s1.into(), name)?)
+}
+
+add_new_op!(BroadcastGradientArgs,
+    constructor: [add_new_op!(BIN CONSTRUCTOR: BroadcastGradientArgs, Init: []);],
+    digest: [DIGEST:
+        fn digest(
+            self,
+            context: &mut Scope,
+            op: OperationData
+        )
+            -> Result<Self::Outputs>
+        {
+            let idtype = IdType::Operation("BroadcastGradientArgs");
+            let dtype = add_new_op!(INPUT0 self);
+            let outputs = register_outputs(context, self.get_op_name(), idtype, dtype, op, 2)?;
+            Ok((outputs[0], outputs[1]))
+        }
+    ],
+    extra_funcs: [], 
+    extra_attr: [],
+    output: [(Tensor, Tensor)],
+);
+
+#[test]
+#[cfg(test)]
+fn test_broadcast_gradient_args() {
+    let mut context = Scope::new();
+    let s0 = context.constant(&[2_i32, 3], &[2], "s0").unwrap();
+    let s1 = context.constant(&[3_i32], &[1], "s1").unwrap();
+    let (r0, r1) = broadcast_gradient_args(&mut context, s0, s1, "").unwrap();
+    let results = test_suite!(run_op: [r0, r1]; context, input: {});
+    test_suite!(results; assert_len: {[0;Int32] == 0});
+    test_suite!(results; assert: {[1;Int32] == [0_i32]});
+}
+
 
 ///// Concat /////
 
@@ -38,7 +100,11 @@ add_new_op!(ConcatV2,
             let output_type = values[0].dtype;
             for x in &values {
                 if &x.dtype != &output_type {
-                    return Err(Error::from(ErrorKind::Stub));
+                    return Err(Error::from(ErrorKind::DTypeMismatch(
+                        "ConcatV2 values".to_owned(),
+                        vec![output_type],
+                        x.dtype,
+                    )));
                 }
             }
 
@@ -84,6 +150,198 @@ fn test_concat() {
 }
 
 
+///// ConcatOffset /////
+
+/// Computes offsets of concat inputs within its output.
+///
+/// For example:
+///
+/// ```python
+/// # 'x' is [2, 2, 7]
+/// # 'y' is [2, 3, 7]
+/// # 'z' is [2, 5, 7]
+/// concat_offset(2, [x, y, z]) => [0, 0, 0], [0, 2, 0], [0, 5, 0]
+/// ```
+///
+/// ### Args
+/// * concat_dim: A `Tensor` of type `int32`. The dimension along which to concatenate.
+/// * shape: A list of at least 2 `Tensor` objects with type `int32`. The `N` int32
+///   vectors representing shape of tensors being concatenated.
+/// * name: A name for the operation (optional).
+///
+/// ### Returns
+/// * A list with the same length as `shape` of `Tensor` objects with type `int32`.
+pub(crate) fn concat_offset<Tx, S>(
+    context: &mut Scope,
+    concat_dim: Tx,
+    shape: Vec<Tensor>,
+    name: S,
+) -> Result<Vec<Tensor>>
+where
+    Tx: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    context.install(ConcatOffset::new(concat_dim.into(), shape, name)?)
+}
+
+add_new_op!(ConcatOffset,
+    constructor: [
+        fn new<S: AsRef<Path>>(concat_dim: Tensor, shape: Vec<Tensor>, name: S) 
+            -> Result<ConcatOffset<'a>> 
+        {
+            if shape.len() < 2 {
+                return Err(Error::from(ErrorKind::InvalidArgument(
+                    "ConcatOffset requires at least two shapes".to_owned(),
+                )));
+            }
+            Ok(
+                ConcatOffset {
+                    ident: NodeIdent::new(),
+                    elements: vec![concat_dim],
+                    name: generate_name!(is_none: name),
+                    attributes: Vec::with_capacity(0),
+                    input_lists: vec![(1, shape)],
+                },
+            )
+        }
+    ],
+    digest: [DIGEST:
+        fn digest(
+            self,
+            context: &mut Scope,
+            op: OperationData
+        )
+            -> Result<Self::Outputs>
+        {
+            let idtype = IdType::Operation("ConcatOffset");
+            let num_outputs = self.input_lists[0].1.len();
+            register_outputs(context, self.get_op_name(), idtype, DataType::Int32, op, num_outputs)
+        }
+    ],
+    extra_funcs: [], 
+    extra_attr: [],
+    output: [Vec<Tensor>],
+);
+
+#[test]
+#[cfg(test)]
+fn test_concat_offset() {
+    let mut context = Scope::new();
+    let axis = context.constant(&[1_i32], &[] as &[i32], "axis").unwrap();
+    let s0 = context.constant(&[2_i32, 2, 7], &[3], "s0").unwrap();
+    let s1 = context.constant(&[2_i32, 3, 7], &[3], "s1").unwrap();
+    let offsets = concat_offset(&mut context, axis, vec![s0.into(), s1.into()], "").unwrap();
+    let (o0, o1) = (offsets[0], offsets[1]);
+    let results = test_suite!(run_op: [o0, o1]; context, input: {});
+    test_suite!(results; assert: {[0;Int32] == [0_i32, 0, 0], [1;Int32] == [0_i32, 2, 0]});
+}
+
+
+///// DynamicStitch /////
+
+/// Interleave the values from the `data` tensors into a single tensor.
+///
+/// Builds a merged tensor such that
+///
+/// ```python
+/// merged[indices[m][i, ..., j], ...] = data[m][i, ..., j, ...]
+/// ```
+///
+/// For example, if each `indices[m]` is scalar or vector, we have
+///
+/// ```python
+/// # Scalar indices:
+/// merged[indices[m], ...] = data[m][...]
+///
+/// # Vector indices:
+/// merged[indices[m][i], ...] = data[m][i, ...]
+/// ```
+///
+/// ### Args
+/// * indices: A list of at least 1 `Tensor` objects with type `int32`.
+/// * data: A list with the same length as `indices` of `Tensor` objects with the same type.
+/// * name: A name for the operation (optional).
+///
+/// ### Returns
+/// * A `Tensor`. Has the same type as `data`.
+pub fn dynamic_stitch<S>(
+    context: &mut Scope,
+    indices: Vec<Tensor>,
+    data: Vec<Tensor>,
+    name: S,
+) -> Result<Tensor>
+where
+    S: AsRef<Path>,
+{
+    context.install(DynamicStitch::new(indices, data, name)?)
+}
+
+add_new_op!(DynamicStitch,
+    constructor: [
+        fn new<S: AsRef<Path>>(indices: Vec<Tensor>, data: Vec<Tensor>, name: S) 
+            -> Result<DynamicStitch<'a>> 
+        {
+            if indices.is_empty() || indices.len() != data.len() {
+                return Err(Error::from(ErrorKind::InvalidArgument(
+                    "DynamicStitch requires the same non-zero number of indices and data".to_owned(),
+                )));
+            }
+            let output_type = data[0].dtype;
+            for x in &data {
+                if x.dtype != output_type {
+                    return Err(Error::from(ErrorKind::DTypeMismatch(
+                        "DynamicStitch data".to_owned(),
+                        vec![output_type],
+                        x.dtype,
+                    )));
+                }
+            }
+            for x in &indices {
+                if x.dtype != DataType::Int32 {
+                    return Err(Error::from(ErrorKind::DTypeMismatch(
+                        "DynamicStitch indices".to_owned(),
+                        vec![DataType::Int32],
+                        x.dtype,
+                    )));
+                }
+            }
+            Ok(
+                DynamicStitch {
+                    ident: NodeIdent::new(),
+                    elements: Vec::with_capacity(0),
+                    name: generate_name!(is_none: name),
+                    attributes: Vec::with_capacity(0),
+                    input_lists: vec![(0, indices), (1, data)],
+                    output_type,
+                },
+            )
+        }
+    ],
+    digest: [DEFAULT_DIGEST: DynamicStitch, DTYPE_ATTR],
+    extra_funcs: [], 
+    extra_attr: [output_type: DataType],
+    output: [Tensor],
+);
+
+#[test]
+#[cfg(test)]
+fn test_dynamic_stitch() {
+    let mut context = Scope::new();
+    let i0 = context.constant(&[0_i32, 2], &[2], "i0").unwrap();
+    let i1 = context.constant(&[1_i32], &[1], "i1").unwrap();
+    let d0 = context.constant(&[10_i32, 30], &[2], "d0").unwrap();
+    let d1 = context.constant(&[20_i32], &[1], "d1").unwrap();
+    let op = dynamic_stitch(
+        &mut context,
+        vec![i0.into(), i1.into()],
+        vec![d0.into(), d1.into()],
+        "",
+    ).unwrap();
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Int32] == [10_i32, 20, 30]});
+}
+
+
 ///// ExpandDims /////
 
 ///   Inserts a dimension of 1 into a tensor's shape.
@@ -235,7 +493,11 @@ where
 {
     let indices = indices.into();
     if indices.dtype != DataType::Int32 && indices.dtype != DataType::Int64 {
-        return Err(Error::from(ErrorKind::Stub));
+        return Err(Error::from(ErrorKind::DTypeMismatch(
+            "gather indices".to_owned(),
+            vec![DataType::Int32, DataType::Int64],
+            indices.dtype,
+        )));
     }
     context.install(Gather::new(params.into(), indices, name)?)
 }
@@ -260,6 +522,169 @@ fn test_gather() {
 }
 
 
+///// InvertPermutation /////
+
+/// Computes the inverse permutation of a tensor.
+///
+/// This operation computes the inverse of an index permutation. It takes a 1-D integer
+/// tensor `x`, which represents the indices of a zero-based array, and swaps each value
+/// with its index position. In other words, for an output tensor `y` and an input tensor
+/// `x`, this operation computes the following: `y[x[i]] = i for i in [0, 1, ..., len(x) - 1]`
+///
+/// The values must include 0. There can be no duplicate values or negative values.
+///
+/// ### Args
+/// * x: A `Tensor`. Must be one of the following types: int32, int64. 1-D.
+/// * name: A name for the operation (optional).
+///
+/// ### Returns
+/// * A `Tensor`. Has the same type as `x`. 1-D.
+pub fn invert_permutation<Tx, S>(context: &mut Scope, x: Tx, name: S) -> Result<Tensor>
+where
+    Tx: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    let x = x.into();
+    if x.dtype != DataType::Int32 && x.dtype != DataType::Int64 {
+        return Err(Error::from(ErrorKind::DTypeMismatch(
+            "invert_permutation input".to_owned(),
+            vec![DataType::Int32, DataType::Int64],
+            x.dtype,
+        )));
+    }
+    context.install(InvertPermutation::new(x, name)?)
+}
+
+add_new_op!(InvertPermutation,
+    constructor: [add_new_op!(UNARY CONSTRUCTOR: InvertPermutation, Init: []);],
+    digest: [DEFAULT_DIGEST: InvertPermutation, INPUT0],
+    extra_funcs: [], 
+    extra_attr: [],
+    output: [Tensor],
+);
+
+#[test]
+#[cfg(test)]
+fn test_invert_permutation() {
+    let mut context = Scope::new();
+    let x = context.constant(&[3_i32, 4, 0, 2, 1], &[5], "x").unwrap();
+    let op = invert_permutation(&mut context, x, "").unwrap();
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Int32] == [2_i32, 4, 3, 0, 1]});
+}
+
+
+///// Pad /////
+
+/// Pads a tensor with zeros.
+///
+/// This operation pads `input` according to the `paddings` you specify. `paddings`
+/// is an integer tensor with shape `[n, 2]`, where n is the rank of `input`. For each
+/// dimension D of `input`, `paddings[D, 0]` indicates how many zeros to add before
+/// the contents of `input` in that dimension, and `paddings[D, 1]` indicates how
+/// many zeros to add after the contents of `input` in that dimension.
+///
+/// ### Args
+/// * input: A `Tensor`.
+/// * paddings: A `Tensor` of type `int32` or `int64`, with shape `[n, 2]`.
+/// * name: A name for the operation (optional).
+///
+/// ### Returns
+/// A `Tensor`. Has the same type as `input`.
+pub fn pad<Tx, Tp, S>(context: &mut Scope, input: Tx, paddings: Tp, name: S) -> Result<Tensor>
+where
+    Tx: Into<Tensor>,
+    Tp: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    context.install(Pad::new(input.into(), paddings.into(), name)?)
+}
+
+add_new_op!(Pad,
+    constructor: [add_new_op!(BIN CONSTRUCTOR: Pad, Init: []);],
+    digest: [DEFAULT_DIGEST: Pad, INPUT0],
+    extra_funcs: [], 
+    extra_attr: [],
+    output: [Tensor],
+);
+
+#[test]
+#[cfg(test)]
+fn test_pad() {
+    let mut context = Scope::new();
+    let x = context.constant(&[1_i32, 2], &[1, 2], "x").unwrap();
+    let paddings = context.constant(&[1_i32, 0, 0, 1], &[2, 2], "paddings").unwrap();
+    let op = pad(&mut context, x, paddings, "").unwrap();
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Int32] == [0_i32, 0, 0, 1, 2, 0]});
+}
+
+
+///// PlaceholderWithDefault /////
+
+/// A placeholder op that passes through `input` when its output is not fed.
+///
+/// ### Args
+/// * input: The default `Tensor` that is produced when the output is not fed.
+/// * shape: The (possibly partial) shape of the tensor.
+/// * name: A name for the operation (optional).
+///
+/// ### Returns
+/// A `Tensor`. Has the same type as `input`.
+pub fn placeholder_with_default<Tx, IS, S>(
+    context: &mut Scope,
+    input: Tx,
+    shape: IS,
+    name: S,
+) -> Result<Tensor>
+where
+    Tx: Into<Tensor>,
+    IS: IntoShape,
+    S: AsRef<Path>,
+{
+    let shape = [shape.to_shape()];
+    context.install(PlaceholderWithDefault::new(input.into(), &shape, name)?)
+}
+
+add_new_op!(PlaceholderWithDefault,
+    constructor: [
+        fn new<S: AsRef<Path>>(input: Tensor, shape: &'a [TensorShape], name: S)
+            -> Result<PlaceholderWithDefault<'a>>
+        {
+            Ok(
+                PlaceholderWithDefault {
+                    ident: NodeIdent::new(),
+                    elements: vec![input],
+                    name: generate_name!(is_none: name),
+                    attributes: vec![("shape", false, Attribute::Shape(shape))],
+                    input_lists: vec![],
+                },
+            )
+        }
+    ],
+    digest: [DEFAULT_DIGEST: PlaceholderWithDefault, INPUT0],
+    extra_funcs: [],
+    extra_attr: [],
+    output: [Tensor],
+);
+
+#[test]
+#[cfg(test)]
+fn test_placeholder_with_default() {
+    let mut context = Scope::new();
+    let default = context.constant(&[1_i32, 2], &[2], "").unwrap();
+    let x = placeholder_with_default(&mut context, default, &[2_i64] as &[i64], "").unwrap();
+    let results = test_suite!(run_op: [x]; context, input: {});
+    test_suite!(results; assert: {[0;Int32] == [1_i32, 2]});
+
+    let mut fed = TypedTensor::<i32>::new(&[2]);
+    fed[0] = 3;
+    fed[1] = 4;
+    let results = test_suite!(run_op: [x]; context, input: {(x, vec![TensorContent::from(fed)])});
+    test_suite!(results; assert: {[0;Int32] == [3_i32, 4]});
+}
+
+
 ///// Rank /////
 
 ///  Returns the rank of a tensor.
@@ -289,13 +714,13 @@ where
     Tx: Into<Tensor>,
     S: AsRef<Path>,
 {
-    let scope = &mut context.name_scope(name.as_ref(), Some("Rank".as_ref()));
+    let scope = &mut context.try_name_scope(name.as_ref(), Some("Rank".as_ref()))?;
     let input_tensor = input_tensor.into();
     // optimize: encode the rank as a constant when possible.
     if let Some(ndim) = input_tensor.get_shape(scope).dims() {
         Ok(scope.constant(&[ndim as i32], &[] as &[i32], "")?.into())
     } else {
-        context.install(Rank::new(input_tensor.into(), "")?)
+        scope.install(Rank::new(input_tensor.into(), "")?)
     }
 }
 
@@ -419,11 +844,19 @@ add_new_op!(Shape,
                 match *dtype {
                     DataType::Int64 => out = DataType::Int64,
                     DataType::Int32 => out = DataType::Int32,
-                    _ => return Err(Error::from(ErrorKind::Stub)),
+                    _ => {
+                        return Err(Error::from(ErrorKind::DTypeMismatch(
+                            "Shape output".to_owned(),
+                            vec![DataType::Int32, DataType::Int64],
+                            *dtype,
+                        )))
+                    }
                 }
                 vec![("out_type", false, Attribute::Type(output_type))]
             } else if output_type.len() > 0 {
-                return Err(Error::from(ErrorKind::Stub));
+                return Err(Error::from(ErrorKind::InvalidArgument(
+                    "Shape takes a single output type".to_owned(),
+                )));
             } else {
                 out = DataType::Int32;
                 Vec::with_capacity(0)
@@ -473,10 +906,12 @@ where
 }
 
 add_new_op!(Size, 
-    constructor: [add_new_op!(UNARY CONSTRUCTOR: Size, Init: []);],
-    digest: [DEFAULT_DIGEST: Size, INPUT0],
+    constructor: [add_new_op!(UNARY CONSTRUCTOR: Size, 
+        Init: [output_type: DataType::Int32]);
+    ],
+    digest: [DEFAULT_DIGEST: Size, DTYPE_ATTR],
     extra_funcs: [], 
-    extra_attr: [],
+    extra_attr: [output_type: DataType],
     output: [Tensor],
 );
 
@@ -624,12 +1059,58 @@ add_new_op!(Slice,
             )
         }
     ],
-    digest: [DEFAULT_DIGEST: Transpose, INPUT0],
+    digest: [DEFAULT_DIGEST: Slice, INPUT0],
+    extra_funcs: [], 
+    extra_attr: [],
+    output: [Tensor],
+);
+
+
+///// Tile /////
+
+/// Constructs a tensor by tiling a given tensor.
+///
+/// This operation creates a new tensor by replicating `input` `multiples` times.
+/// The output tensor's i'th dimension has `input.dims(i) * multiples[i]` elements,
+/// and the values of `input` are replicated `multiples[i]` times along the 'i'th
+/// dimension. For example, tiling `[a b c d]` by `[2]` produces `[a b c d a b c d]`.
+///
+/// ### Args
+/// * input: A `Tensor`. 1-D or higher.
+/// * multiples: A `Tensor` of type `int32` or `int64`. 1-D. Length must be the same
+///   as the number of dimensions in `input`.
+/// * name: A name for the operation (optional).
+///
+/// ### Returns
+/// A `Tensor`. Has the same type as `input`.
+pub fn tile<Tx, Tm, S>(context: &mut Scope, input: Tx, multiples: Tm, name: S) -> Result<Tensor>
+where
+    Tx: Into<Tensor>,
+    Tm: TensorOps,
+    S: AsRef<Path>,
+{
+    let multiples = multiples.into_tensor(context, "");
+    context.install(Tile::new(input.into(), multiples, name)?)
+}
+
+add_new_op!(Tile,
+    constructor: [add_new_op!(BIN CONSTRUCTOR: Tile, Init: []);],
+    digest: [DEFAULT_DIGEST: Tile, INPUT0],
     extra_funcs: [], 
     extra_attr: [],
     output: [Tensor],
 );
 
+#[test]
+#[cfg(test)]
+fn test_tile() {
+    let mut context = Scope::new();
+    let x = context.constant(&[1_i32, 2], &[1, 2], "x").unwrap();
+    let op = tile(&mut context, x, &[2_i32, 2] as &[i32], "").unwrap();
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Int32] == [1_i32, 2, 1, 2, 1, 2, 1, 2]});
+}
+
 
 ///// Transpose /////
 
@@ -687,7 +1168,7 @@ where
     TeS: TensorOps,
     S: AsRef<Path>,
 {
-    let scope = &mut context.name_scope(name.as_ref(), Some("transpose".as_ref()));
+    let scope = &mut context.try_name_scope(name.as_ref(), Some("transpose".as_ref()))?;
     let a = a.into();
     if let Some(perm) = perm {
         //let perm = scope.constant(perm, &[1] as &[i32], "")?.into();
@@ -762,12 +1243,25 @@ where
 {
     let cond = cond.into();
     if cond.dtype != DataType::Bool {
-        return Err(Error::from(ErrorKind::Stub));
+        return Err(Error::from(ErrorKind::DTypeMismatch(
+            "where condition".to_owned(),
+            vec![DataType::Bool],
+            cond.dtype,
+        )));
     }
     if (x.is_none() && y.is_some()) || (x.is_some() && y.is_none()) {
-        return Err(Error::from(ErrorKind::Stub));
-    } else if x.is_some() || y.is_some() {
-        unimplemented!()
+        return Err(Error::from(ErrorKind::InvalidArgument(
+            "either both or none of `x` and `y` must be provided".to_owned(),
+        )));
+    } else if let (Some(x), Some(y)) = (x, y) {
+        if x.dtype != y.dtype {
+            return Err(Error::from(ErrorKind::DTypeMismatch(
+                "where y".to_owned(),
+                vec![x.dtype],
+                y.dtype,
+            )));
+        }
+        context.install(Select::new(cond, x, y, name)?)
     } else {
         context.install(Where::new(cond.into(), name)?)
     }
@@ -783,6 +1277,39 @@ add_new_op!(Where,
     output: [Tensor],
 );
 
+add_new_op!(Select,
+    constructor: [
+        fn new<S: AsRef<Path>>(cond: Tensor, x: Tensor, y: Tensor, name: S) -> Result<Select<'a>> {
+            Ok(
+                Select {
+                    ident: NodeIdent::new(),
+                    output_type: x.dtype,
+                    elements: vec![cond, x, y],
+                    name: generate_name!(is_none: name),
+                    attributes: vec![],
+                    input_lists: vec![],
+                },
+            )
+        }
+    ],
+    digest: [DEFAULT_DIGEST: Select, DTYPE_ATTR],
+    extra_funcs: [],
+    extra_attr: [output_type: DataType],
+    output: [Tensor],
+);
+
+#[test]
+#[cfg(test)]
+fn test_where_select() {
+    let mut context = Scope::new();
+    let cond = context.constant(&[true, false], &[2], "cond").unwrap();
+    let x = context.constant(&[1_i32, 2], &[2], "x").unwrap();
+    let y = context.constant(&[3_i32, 4], &[2], "y").unwrap();
+    let op = where_cond(&mut context, cond, Some(x.into()), Some(y.into()), "").unwrap();
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Int32] == [1_i32, 4]});
+}
+
 
 ///// Zeros /////
 
@@ -817,14 +1344,128 @@ where
         DataType::BFloat16 => context.constant(&[::BFloat16::from(0.)], &[] as &[i32], "")?,
         DataType::Complex64 => context.constant(&[::Complex32::new(0., 0.)], &[] as &[i32], "")?, 
         DataType::Complex128 => context.constant(&[::Complex64::new(0., 0.)], &[] as &[i32], "")?,
-        _ => return Err(Error::from(ErrorKind::Stub)),
+        _ => return Err(Error::from(ErrorKind::UnknownDType(dtype))),
     };
     context.install(Fill::new(shape.into(), zero.into(), name)?)
 }
 
 
+///// OnesLike /////
+
+/// Returns a tensor of ones with the same shape and type as `x`.
+///
+/// ### Args
+/// * x: A `Tensor`.
+/// * name: A name for the operation (optional).
+pub fn ones_like<Tx, S>(context: &mut Scope, x: Tx, name: S) -> Result<Tensor>
+where
+    Tx: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    context.install(OnesLike::new(x.into(), name)?)
+}
+
+add_new_op!(OnesLike,
+    constructor: [add_new_op!(UNARY CONSTRUCTOR: OnesLike, Init: []);],
+    digest: [DEFAULT_DIGEST: OnesLike, INPUT0],
+    extra_funcs: [], 
+    extra_attr: [],
+    output: [Tensor],
+);
+
+
+///// ZerosLike /////
+
+/// Returns a tensor of zeros with the same shape and type as `x`.
+///
+/// ### Args
+/// * x: A `Tensor`.
+/// * name: A name for the operation (optional).
+pub fn zeros_like<Tx, S>(context: &mut Scope, x: Tx, name: S) -> Result<Tensor>
+where
+    Tx: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    context.install(ZerosLike::new(x.into(), name)?)
+}
+
+add_new_op!(ZerosLike,
+    constructor: [add_new_op!(UNARY CONSTRUCTOR: ZerosLike, Init: []);],
+    digest: [DEFAULT_DIGEST: ZerosLike, INPUT0],
+    extra_funcs: [], 
+    extra_attr: [],
+    output: [Tensor],
+);
+
+#[test]
+#[cfg(test)]
+fn test_ones_and_zeros_like() {
+    let mut context = Scope::new();
+    let x = context.constant(&[3_i32, 4, 5], &[3], "x").unwrap();
+    let op1 = ones_like(&mut context, x, "").unwrap();
+    let op2 = zeros_like(&mut context, x, "").unwrap();
+    let results = test_suite!(run_op: [op1, op2]; context, input: {});
+    test_suite!(results; assert: {[0;Int32] == [1_i32, 1, 1], [1;Int32] == [0_i32, 0, 0]});
+}
+
+
 ///// Lower level support ops /////
 
+/// Registers each output of a multi-output operation, all of them with the same dtype.
+fn register_outputs(
+    context: &mut Scope,
+    op_name: Option<&Path>,
+    idtype: IdType,
+    dtype: DataType,
+    op: OperationData,
+    num_outputs: usize,
+) -> Result<Vec<Tensor>> {
+    let full_name = context.resolve_tensor_name(op_name, idtype, false)?;
+    let mut outputs = Vec::with_capacity(num_outputs);
+    for idx in 0..num_outputs {
+        let shape = {
+            let g = context.graph.borrow();
+            g.tensor_shape(Output {
+                operation: op.clone(),
+                index: idx as i32,
+            })?
+        };
+        let ident = NodeIdent::new();
+        let tensor = Tensor {
+            ident,
+            idtype,
+            dtype,
+            idx: idx as i32,
+            initializer: None,
+        };
+        match context.control_context {
+            ControlFlow::CondContext(ref mut cond) => {
+                cond.values.insert(ident);
+                cond.external_values.insert(ident, tensor);
+            }
+            ControlFlow::WhileContext(ref mut cond) => {
+                cond.values.insert(ident);
+                cond.external_values.insert(ident, tensor);
+            }
+            ControlFlow::None => {}
+        }
+        let reg = &mut *context.registry.borrow_mut();
+        context.own_scope.ops.push((full_name.clone(), ident));
+        reg.insert(
+            ident,
+            TensorData {
+                full_name: full_name.clone(),
+                dtype,
+                idtype,
+                data_origin: (op.clone(), idx as i32),
+                shape,
+            },
+        );
+        outputs.push(tensor);
+    }
+    Ok(outputs)
+}
+
 pub(crate) fn constant<'a, T, I>(
     graph: &mut Graph,
     name: &str,
@@ -864,8 +1505,12 @@ pub(crate) fn placeholder(
     graph: &mut Graph,
     name: &str,
     dtype: DataType,
+    shape: Option<&TensorShape>,
 ) -> Result<OperationData> {
     let mut p = graph.new_operation("Placeholder", name)?;
     p.set_attr_type("dtype", dtype)?;
+    if let Some(shape) = shape {
+        p.set_attr_shape("shape", shape)?;
+    }
     Ok(p.finish()?)
 }
@@ -95,7 +95,11 @@ impl<'a> Assert<'a> {
         name: S,
     ) -> Result<Assert<'a>> {
         if condition.dtype != DataType::Bool {
-            return Err(Error::from(ErrorKind::Stub));
+            return Err(Error::from(ErrorKind::DTypeMismatch(
+                "Assert condition".to_owned(),
+                vec![DataType::Bool],
+                condition.dtype,
+            )));
         }
         Ok(Assert {
             ident: NodeIdent::new(),
@@ -160,7 +164,7 @@ where
         vec![x, y]
     };
 
-    let scope = &mut context.name_scope(name.as_ref(), Some("assert_equal".as_ref()));
+    let scope = &mut context.try_name_scope(name.as_ref(), Some("assert_equal".as_ref()))?;
     let eq = equal(scope, x, y, "")?;
     let cond = reduce_all(scope, eq, &[], false, "")?;
     let mut assert = Assert::new(cond, data, "")?;
@@ -204,7 +208,7 @@ where
         vec![x, y]
     };
     
-    let scope = &mut context.name_scope(name.as_ref(), Some("assert_greater".as_ref()));
+    let scope = &mut context.try_name_scope(name.as_ref(), Some("assert_greater".as_ref()))?;
     let eq = greater(scope, x, y, "")?;
     let cond = reduce_all(scope, eq, &[], false, "")?;
     let mut assert = Assert::new(cond, data, "")?;
@@ -235,17 +239,21 @@ where
     S: AsRef<Path>,
 {
     if pred.dtype != DataType::Bool {
-        return Err(Error::from(ErrorKind::Stub));
+        return Err(Error::from(ErrorKind::DTypeMismatch(
+            "cond predicate".to_owned(),
+            vec![DataType::Bool],
+            pred.dtype,
+        )));
     }
     if pred.get_shape(context) != Shape::from(Some(vec![])) {
-        let msg = format!(
-            "tf: expected shape `[]` for pred Tensor on `cond` op call, found shape: `{:?}`",
-            pred.get_shape(context)
-        );
-        return Err(Error::from(msg));
+        return Err(Error::from(ErrorKind::ShapeMismatch(
+            "cond predicate".to_owned(),
+            Shape::from(Some(vec![])),
+            pred.get_shape(context),
+        )));
     }
 
-    let scope = &mut context.name_scope(name.as_ref(), Some("Cond".as_ref()));
+    let scope = &mut context.try_name_scope(name.as_ref(), Some("Cond".as_ref()))?;
     // Add the switch to the graph.
     let (p_0, p_1) = switch(scope, pred, pred, "")?;
 
@@ -264,7 +272,7 @@ where
             name.join("true_branch").to_str().unwrap().to_owned()
         };
         let mut context_t =
-            scope.cond_scope(CondContext::new(pred, pivot_1, 1, name), "true_branch");
+            scope.cond_scope(CondContext::new(pred, pivot_1, 1, name), "true_branch")?;
         context_t.build_cond_branch(true_fn)?
     };
 
@@ -275,7 +283,7 @@ where
             name.join("false_branch").to_str().unwrap().to_owned()
         };
         let mut context_f =
-            scope.cond_scope(CondContext::new(pred, pivot_0, 0, name), "false_branch");
+            scope.cond_scope(CondContext::new(pred, pivot_0, 0, name), "false_branch")?;
         context_f.build_cond_branch(false_fn)?
     };
 
@@ -284,7 +292,14 @@ where
         let res_f = res_f[0];
 
         if (res_t.dtype != res_f.dtype) || (res_t.is_ref() != res_f.is_ref()) {
-            return Err(Error::from(ErrorKind::Stub));
+            return Err(Error::from(ErrorKind::InvalidArgument(format!(
+                "outputs of the cond branches must have the same type, found {:?} (ref: {}) \
+                 and {:?} (ref: {})",
+                res_t.dtype,
+                res_t.is_ref(),
+                res_f.dtype,
+                res_f.is_ref()
+            ))));
         }
 
         if res_t.is_ref() {
@@ -295,7 +310,9 @@ where
     } else if res_t.len() == res_f.len() && !res_t.is_empty() {
         unimplemented!()
     } else {
-        Err(Error::from(ErrorKind::Stub))
+        Err(Error::from(ErrorKind::InvalidArgument(
+            "cond branches must return the same non-zero number of tensors".to_owned(),
+        )))
     }
 }
 
@@ -330,7 +347,8 @@ impl CondContext {
 }
 
 pub(crate) trait CondContextInterface {
-    fn cond_scope<S: AsRef<Path>>(&mut self, cond_context: CondContext, name: S) -> Scope;
+    fn cond_scope<S: AsRef<Path>>(&mut self, cond_context: CondContext, name: S)
+        -> Result<Scope>;
 
     fn process_output_tensor(&mut self, val: &Tensor) -> Tensor;
 
@@ -341,8 +359,12 @@ pub(crate) trait CondContextInterface {
 }
 
 impl CondContextInterface for Scope {
-    fn cond_scope<S: AsRef<Path>>(&mut self, mut cond_context: CondContext, name: S) -> Scope {
-        self.allow_writes();
+    fn cond_scope<S: AsRef<Path>>(
+        &mut self,
+        mut cond_context: CondContext,
+        name: S,
+    ) -> Result<Scope> {
+        self.allow_writes()?;
         let name = self.resolve_new_scope_name(name, "cond");
         let mut context = self.as_new_child(name);
         match context.control_context {
@@ -355,7 +377,7 @@ impl CondContextInterface for Scope {
             _ => {}
         }
         context.control_context = ControlFlow::CondContext(cond_context);
-        context
+        Ok(context)
     }
 
     fn process_output_tensor(&mut self, val: &Tensor) -> Tensor {
@@ -642,7 +664,11 @@ add_new_op!(Merge,
             let output_type = values[0].dtype;
             for x in &values {
                 if &x.dtype != &output_type {
-                    return Err(Error::from(ErrorKind::Stub));
+                    return Err(Error::from(ErrorKind::DTypeMismatch(
+                        "Merge values".to_owned(),
+                        vec![output_type],
+                        x.dtype,
+                    )));
                 }
             }
 
@@ -782,7 +808,11 @@ add_new_op!(RefMerge,
             let output_type = values[0].dtype;
             for x in &values {
                 if &x.dtype != &output_type {
-                    return Err(Error::from(ErrorKind::Stub));
+                    return Err(Error::from(ErrorKind::DTypeMismatch(
+                        "RefMerge values".to_owned(),
+                        vec![output_type],
+                        x.dtype,
+                    )));
                 }
             }
 
@@ -923,7 +953,8 @@ where
         name.as_ref()
     };
 
-    let scope = &mut context.loop_scope(WhileContext::new(name.to_str().unwrap().to_owned()), name);
+    let while_context = WhileContext::new(name.to_str().unwrap().to_owned());
+    let scope = &mut context.loop_scope(while_context, name)?;
     scope.build_loop(pred, body, loop_vars)
 }
 
@@ -963,7 +994,8 @@ impl WhileContext {
 }
 
 pub(crate) trait WhileContextInterface {
-    fn loop_scope<S: AsRef<Path>>(&mut self, cond_context: WhileContext, name: S) -> Scope;
+    fn loop_scope<S: AsRef<Path>>(&mut self, cond_context: WhileContext, name: S)
+        -> Result<Scope>;
     fn build_loop(
         &mut self,
         pred: WhileCondGraph,
@@ -981,8 +1013,12 @@ macro_rules! while_context {
 }
 
 impl WhileContextInterface for Scope {
-    fn loop_scope<S: AsRef<Path>>(&mut self, mut cond_context: WhileContext, name: S) -> Scope {
-        self.allow_writes();
+    fn loop_scope<S: AsRef<Path>>(
+        &mut self,
+        mut cond_context: WhileContext,
+        name: S,
+    ) -> Result<Scope> {
+        self.allow_writes()?;
         let name = self.resolve_new_scope_name(name, "cond");
         let mut context = self.as_new_child(name);
         match context.control_context {
@@ -993,7 +1029,7 @@ impl WhileContextInterface for Scope {
             _ => {}
         }
         context.control_context = ControlFlow::WhileContext(cond_context);
-        context
+        Ok(context)
     }
 
     fn build_loop(
@@ -1115,14 +1151,18 @@ fn loop_cond<S: AsRef<Path>>(
     name: S,
 ) -> Result<Tensor> {
     if pred.dtype != DataType::Bool {
-        return Err(Error::from(ErrorKind::Stub));
+        return Err(Error::from(ErrorKind::DTypeMismatch(
+            "LoopCond predicate".to_owned(),
+            vec![DataType::Bool],
+            pred.dtype,
+        )));
     }
     if pred.get_shape(context) != Shape::from(Some(vec![])) {
-        let msg = format!(
-            "tf: expected shape `[]` for pred Tensor on `cond` op call, found shape: `{:?}`",
-            pred.get_shape(context)
-        );
-        return Err(Error::from(msg));
+        return Err(Error::from(ErrorKind::ShapeMismatch(
+            "LoopCond predicate".to_owned(),
+            Shape::from(Some(vec![])),
+            pred.get_shape(context),
+        )));
     }
     context.install(LoopCond::new(pred, name)?)
 }
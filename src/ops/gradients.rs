@@ -0,0 +1,1303 @@
+//! Symbolic gradient construction.
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::*;
+use super::array_ops::{broadcast_gradient_args, concat_offset};
+use super::math_ops::{reduce_sum_dynamic, tanh_grad};
+use train::nn::{bias_add, bias_add_grad, relu_grad};
+
+/// An operation output, identified by the name of the operation and the output index.
+type Endpoint = (String, i32);
+
+/// Signature of the functions which compute the gradients of an operation.
+///
+/// Takes the operation and the gradients with respect to each of its outputs (`None` if
+/// an output does not contribute to the differentiated tensors) and returns the gradients
+/// with respect to each of its inputs (`None` for the inputs which are not differentiable).
+pub type GradFn = fn(&mut Scope, &GradOp, &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>>;
+
+/// View of an operation for which gradients are being computed.
+#[derive(Debug, Clone)]
+pub struct GradOp {
+    name: String,
+    record: OpRecord,
+    outputs: Vec<Option<Tensor>>,
+}
+
+impl GradOp {
+    /// Name of the operation in the graph.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Type of the operation, as returned by `Operation::get_op_type_name`.
+    pub fn op_type(&self) -> &'static str {
+        self.record.op_type
+    }
+
+    pub fn num_inputs(&self) -> usize {
+        self.record.inputs.len()
+    }
+
+    /// Returns the input at `idx`, input lists are flattened in place.
+    pub fn input(&self, idx: usize) -> Tensor {
+        self.record.inputs[idx]
+    }
+
+    pub fn output(&self, idx: usize) -> Result<Tensor> {
+        match self.outputs.get(idx) {
+            Some(&Some(output)) => Ok(output),
+            _ => Err(Error::from(ErrorKind::InvalidArgument(format!(
+                "output {} of operation `{}` is not registered",
+                idx,
+                self.name
+            )))),
+        }
+    }
+
+    pub fn get_attr_bool(&self, name: &str) -> Option<bool> {
+        self.record.get_attr_bool(name)
+    }
+
+    pub fn get_attr_int(&self, name: &str) -> Option<i64> {
+        self.record.get_attr_int(name)
+    }
+
+    pub fn get_attr_string(&self, name: &str) -> Option<&str> {
+        self.record.get_attr_string(name)
+    }
+
+    pub fn get_attr_type(&self, name: &str) -> Option<DataType> {
+        self.record.get_attr_type(name)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum GradientEntry {
+    Gradient(GradFn),
+    NotDifferentiable,
+}
+
+/// Gradient functions keyed by operation type (as returned by `Operation::get_op_type_name`).
+///
+/// Shared by a root scope and all its children, it's initialized with the gradients
+/// of the ops defined in this crate.
+#[derive(Debug, Clone)]
+pub(crate) struct GradientRegistry {
+    entries: HashMap<String, GradientEntry>,
+}
+
+impl GradientRegistry {
+    /// Registers the gradients of the differentiable ops of this crate.
+    ///
+    /// The ops of while loops (`Enter`, `Exit`, `NextIteration` and `LoopCond`) are
+    /// registered as not differentiable, so backpropagation stops at the outputs of a
+    /// `while_loop`; conditionals built with `cond` are differentiable through their
+    /// `Switch` and `Merge` ops. The ops which update variables or run side effects
+    /// (assignments, `Apply*` optimizer ops, `Assert`, `SaveV2` and `RestoreV2`) are
+    /// not differentiable either.
+    pub fn new() -> GradientRegistry {
+        let mut registry = GradientRegistry { entries: HashMap::new() };
+        let gradients: &[(&str, GradFn)] = &[
+            ("Add", add_grad),
+            ("AddN", add_n_grad),
+            ("BiasAdd", bias_add_grad_fn),
+            ("BiasAddGrad", bias_add_grad_grad),
+            ("Cast", cast_grad),
+            ("ConcatV2", concat_v2_grad),
+            ("Conj", conj_grad),
+            ("Cos", cos_grad),
+            ("Div", div_grad),
+            ("DynamicStitch", dynamic_stitch_grad),
+            ("Exp", exp_grad),
+            ("ExpandDims", reshape_grad),
+            ("Fill", fill_grad),
+            ("FloorMod", floor_mod_grad),
+            ("Gather", gather_grad),
+            ("Identity", identity_grad),
+            ("Log", log_grad),
+            ("LogSoftmax", log_softmax_grad),
+            ("MatMul", matmul_grad),
+            ("Max", max_grad),
+            ("Maximum", maximum_grad),
+            ("Merge", merge_grad),
+            ("Minimum", minimum_grad),
+            ("Mul", mul_grad),
+            ("Neg", neg_grad),
+            ("Pad", pad_grad),
+            ("PlaceholderWithDefault", identity_grad),
+            ("Pow", pow_grad),
+            ("RefMerge", merge_grad),
+            ("RefSwitch", switch_grad),
+            ("Relu", relu_grad_fn),
+            ("ReluGrad", relu_grad_grad),
+            ("Reshape", reshape_grad),
+            ("Select", select_grad),
+            ("Sin", sin_grad),
+            ("Slice", slice_grad),
+            ("Softmax", softmax_grad),
+            ("SparseMatMul", matmul_grad),
+            ("Squeeze", reshape_grad),
+            ("Sub", sub_grad),
+            ("Sum", sum_grad),
+            ("Switch", switch_grad),
+            ("Tanh", tanh_grad_fn),
+            ("TanhGrad", tanh_grad_grad),
+            ("Tile", tile_grad),
+            ("Transpose", transpose_grad),
+            ("UnsortedSegmentSum", unsorted_segment_sum_grad),
+        ];
+        for &(op_type, grad_fn) in gradients {
+            registry.register(op_type, grad_fn).unwrap();
+        }
+        let not_differentiable = [
+            "All",
+            "ApplyAdam",
+            "ApplyGradientDescent",
+            "ApplyMomentum",
+            "Assert",
+            "Assign",
+            "AssignAdd",
+            "AssignSub",
+            "BroadcastGradientArgs",
+            "Ceil",
+            "ConcatOffset",
+            "Enter",
+            "Equal",
+            "Exit",
+            "Floor",
+            "Greater",
+            "InvertPermutation",
+            "Less",
+            "LogicalNot",
+            "LoopCond",
+            "NextIteration",
+            "NoOp",
+            "OnesLike",
+            "RandomStandardNormal",
+            "Range",
+            "Rank",
+            "RefEnter",
+            "RefExit",
+            "RefNextIteration",
+            "RestoreV2",
+            "SaveV2",
+            "Shape",
+            "Size",
+            "StopGradient",
+            "Where",
+            "ZerosLike",
+        ];
+        for op_type in &not_differentiable {
+            registry.not_differentiable(op_type).unwrap();
+        }
+        registry
+    }
+
+    pub fn register(&mut self, op_type: &str, grad_fn: GradFn) -> Result<()> {
+        self.insert(op_type, GradientEntry::Gradient(grad_fn))
+    }
+
+    pub fn not_differentiable(&mut self, op_type: &str) -> Result<()> {
+        self.insert(op_type, GradientEntry::NotDifferentiable)
+    }
+
+    fn insert(&mut self, op_type: &str, entry: GradientEntry) -> Result<()> {
+        if self.entries.contains_key(op_type) {
+            return Err(Error::from(ErrorKind::InvalidArgument(format!(
+                "a gradient for operation type `{}` is already registered",
+                op_type
+            ))));
+        }
+        self.entries.insert(op_type.to_owned(), entry);
+        Ok(())
+    }
+
+    fn get(&self, op_type: &str) -> Option<GradientEntry> {
+        self.entries.get(op_type).cloned()
+    }
+}
+
+struct PendingOp {
+    /// Ops without a record (constants, variables, placeholders...) are not differentiated.
+    grad_op: Option<GradOp>,
+    inputs: Vec<Endpoint>,
+    num_outputs: usize,
+    /// Number of consumers which still have to backpropagate into this op.
+    pending: usize,
+}
+
+/// Constructs symbolic partial derivatives of sum of `ys` w.r.t. each tensor in `xs`.
+///
+/// `ys` and `xs` are each a slice of tensors, `grad_ys` is an optional slice of tensors
+/// holding the gradients received by the `ys`; it must be the same length as `ys`.
+/// When `grad_ys` is `None`, a tensor of ones of the same shape as each `y` is used.
+///
+/// The gradient ops are added to the graph under a `gradients` name scope, walking back
+/// from the `ys` through the operations which were installed in this scope.
+///
+/// Returns a vector of the same length as `xs` where each element is the sum of
+/// `dy/dx` for all the `y` in `ys`, or `None` if there is no path from the `x` to any `y`.
+///
+/// An error is returned if an operation in the path from the `xs` to the `ys` has no
+/// gradient registered, see `Scope::register_gradient`. Operations registered as not
+/// differentiable (see `Scope::not_differentiable`) stop the backpropagation; this
+/// includes the ops of while loops, so no gradient flows through a `while_loop`.
+///
+/// The inputs of operations which were added directly to the graph (e.g. imported with
+/// `Scope::import_graph_def`) are not known, so an error is also returned if one of them
+/// is reached walking back from the `ys`, unless it's the op of an `x` and every `x` was
+/// reached.
+pub fn gradients(
+    context: &mut Scope,
+    ys: &[Tensor],
+    xs: &[Tensor],
+    grad_ys: Option<&[Tensor]>,
+) -> Result<Vec<Option<Tensor>>> {
+    if let Some(grad_ys) = grad_ys {
+        if grad_ys.len() != ys.len() {
+            return Err(Error::from(ErrorKind::InvalidArgument(format!(
+                "passed {} grad_ys for {} ys",
+                grad_ys.len(),
+                ys.len()
+            ))));
+        }
+    }
+    let scope = &mut context.try_name_scope("gradients", None)?;
+
+    let (mut ops, between, y_endpoints, x_endpoints) = {
+        let registry = &*scope.registry.borrow();
+        let records = &*scope.op_records.borrow();
+        let endpoint = |tensor: &Tensor| -> Result<Endpoint> {
+            let data = registry.get(&tensor.ident).ok_or_else(|| {
+                Error::from(ErrorKind::InvalidArgument(
+                    "tensor not found in the scope passed to `gradients`".to_owned(),
+                ))
+            })?;
+            let (ref op, idx) = data.data_origin;
+            Ok((op.name()?, idx))
+        };
+
+        let mut outputs: HashMap<String, Vec<Option<Tensor>>> = HashMap::new();
+        let mut num_inputs: HashMap<String, usize> = HashMap::new();
+        for (ident, data) in registry {
+            let (ref op, idx) = data.data_origin;
+            num_inputs.insert(op.name()?, op.num_inputs());
+            let op_outputs = outputs.entry(op.name()?).or_insert_with(Vec::new);
+            if op_outputs.len() <= idx as usize {
+                op_outputs.resize(idx as usize + 1, None);
+            }
+            if op_outputs[idx as usize].is_none() {
+                op_outputs[idx as usize] = Some(Tensor {
+                    ident: *ident,
+                    idtype: data.idtype,
+                    dtype: data.dtype,
+                    idx,
+                    initializer: None,
+                });
+            }
+        }
+
+        let mut y_endpoints = Vec::with_capacity(ys.len());
+        for y in ys {
+            y_endpoints.push(endpoint(y)?);
+        }
+        let mut x_endpoints = Vec::with_capacity(xs.len());
+        for x in xs {
+            x_endpoints.push(endpoint(x)?);
+        }
+
+        // ops reachable walking back from the ys
+        let mut inputs: HashMap<String, Vec<Endpoint>> = HashMap::new();
+        let mut stack: Vec<String> = y_endpoints.iter().map(|&(ref op, _)| op.clone()).collect();
+        let mut opaque = vec![];
+        while let Some(op) = stack.pop() {
+            if inputs.contains_key(&op) {
+                continue;
+            }
+            let mut op_inputs = vec![];
+            if let Some(record) = records.get(&op) {
+                for input in &record.inputs {
+                    let input = endpoint(input)?;
+                    stack.push(input.0.clone());
+                    op_inputs.push(input);
+                }
+            } else if num_inputs.get(&op).cloned().unwrap_or(0) > 0 {
+                // the inputs of ops which were not installed through a scope are unknown
+                opaque.push(op.clone());
+            }
+            inputs.insert(op, op_inputs);
+        }
+        // the gradient of an x doesn't flow through its own op, but any x which was not
+        // reached could be behind one of the opaque ops
+        let is_x_op = |op: &String| x_endpoints.iter().any(|&(ref x_op, _)| x_op == op);
+        let all_reached = x_endpoints.iter().all(|&(ref op, _)| inputs.contains_key(op));
+        opaque.sort();
+        if let Some(op) = opaque.iter().find(|op| !all_reached || !is_x_op(*op)) {
+            return Err(Error::from(ErrorKind::InvalidArgument(format!(
+                "can't backpropagate through operation `{}`, it was not created through a \
+                 scope (e.g. it was imported from a `GraphDef`)",
+                op
+            ))));
+        }
+
+        // of those, the ops reachable walking forward from the xs
+        let mut consumers: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (op, op_inputs) in &inputs {
+            for &(ref input, _) in op_inputs {
+                consumers.entry(input.as_str()).or_insert_with(Vec::new).push(op.as_str());
+            }
+        }
+        let mut between: HashSet<String> = HashSet::new();
+        let mut stack: Vec<&str> = x_endpoints
+            .iter()
+            .map(|&(ref op, _)| op.as_str())
+            .filter(|op| inputs.contains_key(*op))
+            .collect();
+        while let Some(op) = stack.pop() {
+            if !between.insert(op.to_owned()) {
+                continue;
+            }
+            if let Some(op_consumers) = consumers.get(op) {
+                stack.extend(op_consumers.iter().cloned());
+            }
+        }
+
+        let mut ops: HashMap<String, PendingOp> = HashMap::new();
+        for op in &between {
+            let num_outputs = outputs.get(op).map(|x| x.len()).unwrap_or(0);
+            let grad_op = records.get(op).map(|record| GradOp {
+                name: op.clone(),
+                record: record.clone(),
+                outputs: outputs.get(op).cloned().unwrap_or_else(Vec::new),
+            });
+            ops.insert(
+                op.clone(),
+                PendingOp {
+                    grad_op,
+                    inputs: inputs[op].clone(),
+                    num_outputs,
+                    pending: 0,
+                },
+            );
+        }
+        for op in &between {
+            for &(ref input, _) in &inputs[op] {
+                if let Some(input_op) = ops.get_mut(input) {
+                    input_op.pending += 1;
+                }
+            }
+        }
+        (ops, between, y_endpoints, x_endpoints)
+    };
+
+    let mut grads: HashMap<Endpoint, Vec<Tensor>> = HashMap::new();
+    for (i, y) in ys.iter().enumerate() {
+        let grad_y = if let Some(grad_ys) = grad_ys {
+            grad_ys[i]
+        } else {
+            ones_like(scope, *y, "")?
+        };
+        grads.entry(y_endpoints[i].clone()).or_insert_with(Vec::new).push(grad_y);
+    }
+
+    let x_set: HashSet<&Endpoint> = x_endpoints.iter().collect();
+    let mut ready: Vec<String> = ops.iter()
+        .filter(|&(_, op)| op.pending == 0)
+        .map(|(name, _)| name.clone())
+        .collect();
+    ready.sort();
+    let mut queue: VecDeque<String> = ready.into_iter().collect();
+    while let Some(name) = queue.pop_front() {
+        let op = ops.remove(&name).unwrap();
+        let mut out_grads = Vec::with_capacity(op.num_outputs);
+        for idx in 0..op.num_outputs {
+            out_grads.push(aggregate_grads(scope, &mut grads, &(name.clone(), idx as i32))?);
+        }
+
+        let needed: Vec<bool> = op.inputs
+            .iter()
+            .map(|input| x_set.contains(input) || between.contains(&input.0))
+            .collect();
+        if let Some(ref grad_op) = op.grad_op {
+            if out_grads.iter().any(|x| x.is_some()) && needed.iter().any(|x| *x) {
+                let entry = scope.gradient_registry.borrow().get(grad_op.op_type());
+                let in_grads = match entry {
+                    Some(GradientEntry::Gradient(grad_fn)) => grad_fn(scope, grad_op, &out_grads)?,
+                    Some(GradientEntry::NotDifferentiable) => vec![],
+                    None => {
+                        return Err(Error::from(ErrorKind::InvalidArgument(format!(
+                            "no gradient defined for operation type `{}`",
+                            grad_op.op_type()
+                        ))))
+                    }
+                };
+                for ((input, grad), is_needed) in op.inputs.iter().zip(in_grads).zip(&needed) {
+                    if let (Some(grad), true) = (grad, *is_needed) {
+                        grads.entry(input.clone()).or_insert_with(Vec::new).push(grad);
+                    }
+                }
+            }
+        }
+
+        for &(ref input, _) in &op.inputs {
+            if let Some(input_op) = ops.get_mut(input) {
+                input_op.pending -= 1;
+                if input_op.pending == 0 {
+                    queue.push_back(input.clone());
+                }
+            }
+        }
+    }
+
+    let mut x_grads = Vec::with_capacity(xs.len());
+    for x in &x_endpoints {
+        x_grads.push(aggregate_grads(scope, &mut grads, x)?);
+    }
+    Ok(x_grads)
+}
+
+/// Sums all the gradients received by an endpoint, caching the result.
+fn aggregate_grads(
+    scope: &mut Scope,
+    grads: &mut HashMap<Endpoint, Vec<Tensor>>,
+    endpoint: &Endpoint,
+) -> Result<Option<Tensor>> {
+    let total = match grads.get(endpoint) {
+        None => return Ok(None),
+        Some(received) if received.len() == 1 => return Ok(Some(received[0])),
+        Some(received) => add_n(scope, received.clone(), "")?,
+    };
+    grads.insert(endpoint.clone(), vec![total]);
+    Ok(Some(total))
+}
+
+fn single_grad(grads: &[Option<Tensor>]) -> Result<Tensor> {
+    grads.get(0).and_then(|x| *x).ok_or_else(|| {
+        Error::from(ErrorKind::InvalidArgument(
+            "expected the gradient of the first output of the op".to_owned(),
+        ))
+    })
+}
+
+/// Reduces the gradients of a broadcasting binary op so they match the shape of its inputs.
+fn broadcast_grads(
+    scope: &mut Scope,
+    x: Tensor,
+    y: Tensor,
+    gx: Tensor,
+    gy: Tensor,
+) -> Result<Vec<Option<Tensor>>> {
+    let x_shape = x.get_shape(scope).definition_i64();
+    if x_shape.is_some() && x_shape == y.get_shape(scope).definition_i64() {
+        return Ok(vec![Some(gx), Some(gy)]);
+    }
+    let sx = shape(scope, x, None, "")?;
+    let sy = shape(scope, y, None, "")?;
+    let (rx, ry) = broadcast_gradient_args(scope, sx, sy, "")?;
+    let gx = {
+        let sum = reduce_sum_dynamic(scope, gx, rx, false, "")?;
+        reshape(scope, sum, sx, "")?
+    };
+    let gy = {
+        let sum = reduce_sum_dynamic(scope, gy, ry, false, "")?;
+        reshape(scope, sum, sy, "")?
+    };
+    Ok(vec![Some(gx), Some(gy)])
+}
+
+fn add_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    broadcast_grads(scope, op.input(0), op.input(1), grad, grad)
+}
+
+fn sub_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let gy = negative(scope, grad, "")?;
+    broadcast_grads(scope, op.input(0), op.input(1), grad, gy)
+}
+
+fn mul_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let (x, y) = (op.input(0), op.input(1));
+    let gx = multiply(scope, grad, y, "")?;
+    let gy = multiply(scope, x, grad, "")?;
+    broadcast_grads(scope, x, y, gx, gy)
+}
+
+fn div_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let (x, y) = (op.input(0), op.input(1));
+    let gx = divide(scope, grad, y, "")?;
+    // grad * (-x / y^2)
+    let gy = {
+        let neg_x = negative(scope, x, "")?;
+        let d0 = divide(scope, neg_x, y, "")?;
+        let d1 = divide(scope, d0, y, "")?;
+        multiply(scope, grad, d1, "")?
+    };
+    broadcast_grads(scope, x, y, gx, gy)
+}
+
+fn add_n_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    Ok(vec![Some(grad); op.num_inputs()])
+}
+
+fn identity_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    Ok(vec![Some(single_grad(grads)?)])
+}
+
+fn neg_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    Ok(vec![Some(negative(scope, grad, "")?)])
+}
+
+fn cast_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let src_type = op.input(0).dtype;
+    let differentiable = |dtype: DataType| dtype.is_floating() || dtype.is_complex();
+    if differentiable(src_type) && differentiable(grad.dtype) {
+        Ok(vec![Some(cast(scope, grad, src_type, "")?)])
+    } else {
+        Ok(vec![None])
+    }
+}
+
+fn pow_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let (x, y) = (op.input(0), op.input(1));
+    let z = op.output(0)?;
+    // grad * y * x^(y - 1)
+    let gx = {
+        let ones = ones_like(scope, y, "")?;
+        let y_1 = sub(scope, y, ones, "")?;
+        let p = pow(scope, x, y_1, "")?;
+        let prod = multiply(scope, y, p, "")?;
+        multiply(scope, grad, prod, "")?
+    };
+    // grad * z * log(x), taking log(x) as zero where x <= 0
+    let gy = {
+        let zeros = zeros_like(scope, x, "")?;
+        let mask = greater(scope, x, zeros, "")?;
+        let ones = ones_like(scope, x, "")?;
+        let safe_x = where_cond(scope, mask, Some(x), Some(ones), "")?;
+        let log_x = log(scope, safe_x, "")?;
+        let log_x = where_cond(scope, mask, Some(log_x), Some(zeros), "")?;
+        let prod = multiply(scope, grad, z, "")?;
+        multiply(scope, prod, log_x, "")?
+    };
+    broadcast_grads(scope, x, y, gx, gy)
+}
+
+fn floor_mod_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    // x mod y = x - floor(x / y) * y
+    let grad = single_grad(grads)?;
+    let (x, y) = (op.input(0), op.input(1));
+    let gy = {
+        let div = divide(scope, x, y, "")?;
+        let floor_div = floor(scope, div, "")?;
+        let prod = multiply(scope, grad, floor_div, "")?;
+        negative(scope, prod, "")?
+    };
+    broadcast_grads(scope, x, y, grad, gy)
+}
+
+/// Gradient of `Maximum` (`select_x` is `x >= y`) and `Minimum` (`select_x` is `x <= y`).
+fn maximum_minimum_grad(
+    scope: &mut Scope,
+    op: &GradOp,
+    grad: Tensor,
+    select_x: Tensor,
+) -> Result<Vec<Option<Tensor>>> {
+    let zeros = zeros_like(scope, grad, "")?;
+    let gx = where_cond(scope, select_x, Some(grad), Some(zeros), "")?;
+    let gy = where_cond(scope, select_x, Some(zeros), Some(grad), "")?;
+    broadcast_grads(scope, op.input(0), op.input(1), gx, gy)
+}
+
+fn maximum_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let select_x = {
+        let lt = less(scope, op.input(0), op.input(1), "")?;
+        logical_not(scope, lt, "")?
+    };
+    maximum_minimum_grad(scope, op, grad, select_x)
+}
+
+fn minimum_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let select_x = {
+        let gt = greater(scope, op.input(0), op.input(1), "")?;
+        logical_not(scope, gt, "")?
+    };
+    maximum_minimum_grad(scope, op, grad, select_x)
+}
+
+fn conj_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    Ok(vec![Some(conj(scope, grad, "")?)])
+}
+
+fn matmul_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let (a, b) = (op.input(0), op.input(1));
+    let t_a = op.get_attr_bool("transpose_a").unwrap_or(false);
+    let t_b = op.get_attr_bool("transpose_b").unwrap_or(false);
+    let (grad_a, grad_b) = match (t_a, t_b) {
+        (false, false) => (
+            matmul(scope, grad, b, false, true, false, false, false, false, "")?,
+            matmul(scope, a, grad, true, false, false, false, false, false, "")?,
+        ),
+        (false, true) => (
+            matmul(scope, grad, b, false, false, false, false, false, false, "")?,
+            matmul(scope, grad, a, true, false, false, false, false, false, "")?,
+        ),
+        (true, false) => (
+            matmul(scope, b, grad, false, true, false, false, false, false, "")?,
+            matmul(scope, a, grad, false, false, false, false, false, false, "")?,
+        ),
+        (true, true) => (
+            matmul(scope, b, grad, true, true, false, false, false, false, "")?,
+            matmul(scope, grad, a, true, true, false, false, false, false, "")?,
+        ),
+    };
+    Ok(vec![Some(grad_a), Some(grad_b)])
+}
+
+fn exp_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let y = op.output(0)?;
+    Ok(vec![Some(multiply(scope, grad, y, "")?)])
+}
+
+fn log_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    Ok(vec![Some(divide(scope, grad, op.input(0), "")?)])
+}
+
+fn cos_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let sin_x = sin(scope, op.input(0), "")?;
+    let prod = multiply(scope, grad, sin_x, "")?;
+    Ok(vec![Some(negative(scope, prod, "")?)])
+}
+
+fn sin_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let cos_x = cos(scope, op.input(0), "")?;
+    Ok(vec![Some(multiply(scope, grad, cos_x, "")?)])
+}
+
+fn tanh_grad_fn(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let y = op.output(0)?;
+    Ok(vec![Some(tanh_grad(scope, y, grad, "")?)])
+}
+
+fn tanh_grad_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    // TanhGrad(y, dy) = dy * (1 - y^2)
+    let grad = single_grad(grads)?;
+    let (y, dy) = (op.input(0), op.input(1));
+    let gy = {
+        let prod = multiply(scope, grad, y, "")?;
+        let prod = multiply(scope, prod, dy, "")?;
+        let double = add(scope, prod, prod, "")?;
+        negative(scope, double, "")?
+    };
+    Ok(vec![Some(gy), Some(tanh_grad(scope, y, grad, "")?)])
+}
+
+fn relu_grad_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let features = op.input(1);
+    Ok(vec![
+        Some(relu_grad(scope, grad, features, "")?),
+        Some(zeros_like(scope, features, "")?),
+    ])
+}
+
+fn relu_grad_fn(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let y = op.output(0)?;
+    Ok(vec![Some(relu_grad(scope, grad, y, "")?)])
+}
+
+fn select_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let cond = op.input(0);
+    let zeros = zeros_like(scope, op.input(1), "")?;
+    let gx = where_cond(scope, cond, Some(grad), Some(zeros), "")?;
+    let gy = where_cond(scope, cond, Some(zeros), Some(grad), "")?;
+    Ok(vec![None, Some(gx), Some(gy)])
+}
+
+fn softmax_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    // (grad - sum(grad * softmax, -1, keep_dims)) * softmax
+    let grad = single_grad(grads)?;
+    let y = op.output(0)?;
+    let prod = multiply(scope, grad, y, "")?;
+    let sum = reduce_sum(scope, prod, &[-1_i32], true, "")?;
+    let diff = sub(scope, grad, sum, "")?;
+    Ok(vec![Some(multiply(scope, diff, y, "")?)])
+}
+
+fn log_softmax_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    // grad - sum(grad, -1, keep_dims) * exp(log_softmax)
+    let grad = single_grad(grads)?;
+    let y = op.output(0)?;
+    let sum = reduce_sum(scope, grad, &[-1_i32], true, "")?;
+    let softmax = exp(scope, y, "")?;
+    let prod = multiply(scope, sum, softmax, "")?;
+    Ok(vec![Some(sub(scope, grad, prod, "")?)])
+}
+
+fn bias_add_grad_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    // broadcast the gradient of the bias back to the shape of the input
+    let grad = single_grad(grads)?;
+    let data_format = op.get_attr_string("data_format");
+    let zeros = zeros_like(scope, op.input(0), "")?;
+    Ok(vec![Some(bias_add(scope, zeros, grad, data_format, "")?)])
+}
+
+fn bias_add_grad_fn(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let data_format = op.get_attr_string("data_format");
+    Ok(vec![Some(grad), Some(bias_add_grad(scope, grad, data_format, "")?)])
+}
+
+/// Returns the shape of a tensor of `input_shape` reduced along `axes` keeping dimensions.
+fn reduced_shape(scope: &mut Scope, input_shape: Tensor, axes: Tensor) -> Result<Tensor> {
+    let input_rank = size(scope, input_shape, "")?;
+    let axes = floor_mod(scope, axes, input_rank, "")?;
+    let axes_shape = shape(scope, axes, None, "")?;
+    let indices = range(scope, 0_i32, input_rank, 1_i32, "")?;
+    let ones = fill(scope, axes_shape, 1_i32, "")?;
+    dynamic_stitch(scope, vec![indices, axes], vec![input_shape, ones], "")
+}
+
+fn sum_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let x = op.input(0);
+    let mut axes = op.input(1);
+    if axes.dtype != DataType::Int32 {
+        axes = cast(scope, axes, DataType::Int32, "")?;
+    }
+    let input_shape = shape(scope, x, None, "")?;
+    let output_shape_kept_dims = reduced_shape(scope, input_shape, axes)?;
+    let grad = reshape(scope, grad, output_shape_kept_dims, "")?;
+    let tile_scaling = safe_shape_div(scope, input_shape, output_shape_kept_dims)?;
+    Ok(vec![Some(tile(scope, grad, tile_scaling, "")?), None])
+}
+
+/// Divides two shapes, the dimensions of size zero of `y` are treated as ones.
+fn safe_shape_div(scope: &mut Scope, x: Tensor, y: Tensor) -> Result<Tensor> {
+    let ones = ones_like(scope, y, "")?;
+    let y = maximum(scope, y, ones, "")?;
+    divide(scope, x, y, "")
+}
+
+fn max_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    // the gradient is split evenly between the elements equal to the maximum
+    let grad = single_grad(grads)?;
+    let x = op.input(0);
+    let mut axes = op.input(1);
+    if axes.dtype != DataType::Int32 {
+        axes = cast(scope, axes, DataType::Int32, "")?;
+    }
+    let input_shape = shape(scope, x, None, "")?;
+    let output_shape_kept_dims = reduced_shape(scope, input_shape, axes)?;
+    let y = {
+        let y = op.output(0)?;
+        reshape(scope, y, output_shape_kept_dims, "")?
+    };
+    let grad = reshape(scope, grad, output_shape_kept_dims, "")?;
+    let indicators = {
+        let eq = equal(scope, y, x, "")?;
+        cast(scope, eq, grad.dtype, "")?
+    };
+    let num_selected = {
+        let sum = reduce_sum_dynamic(scope, indicators, axes, false, "")?;
+        reshape(scope, sum, output_shape_kept_dims, "")?
+    };
+    let share = divide(scope, indicators, num_selected, "")?;
+    Ok(vec![Some(multiply(scope, share, grad, "")?), None])
+}
+
+fn fill_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let axes = {
+        let r = rank(scope, grad, "")?;
+        range(scope, 0_i32, r, 1_i32, "")?
+    };
+    Ok(vec![None, Some(reduce_sum_dynamic(scope, grad, axes, false, "")?)])
+}
+
+fn tile_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    // reshape the gradient to [multiples[0], input_shape[0], multiples[1], ...]
+    // and sum over the multiples
+    let grad = single_grad(grads)?;
+    let mut multiples = op.input(1);
+    if multiples.dtype != DataType::Int32 {
+        multiples = cast(scope, multiples, DataType::Int32, "")?;
+    }
+    let input_shape = shape(scope, op.input(0), None, "")?;
+    let split_shape = {
+        let m = expand_dims(scope, multiples, 0, "")?;
+        let s = expand_dims(scope, input_shape, 0, "")?;
+        let stacked = concat(scope, vec![m, s], 0, "")?;
+        let t = transpose(scope, stacked, Some(&[1_i32, 0] as &[i32]), "")?;
+        reshape(scope, t, &[-1_i32] as &[i32], "")?
+    };
+    let axes = {
+        let n = size(scope, split_shape, "")?;
+        range(scope, 0_i32, n, 2_i32, "")?
+    };
+    let grad = reshape(scope, grad, split_shape, "")?;
+    Ok(vec![Some(reduce_sum_dynamic(scope, grad, axes, false, "")?), None])
+}
+
+fn slice_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    // pad the gradient with zeros before and after the slice
+    let grad = single_grad(grads)?;
+    let x = op.input(0);
+    let mut begin = op.input(1);
+    if begin.dtype != DataType::Int32 {
+        begin = cast(scope, begin, DataType::Int32, "")?;
+    }
+    let input_shape = shape(scope, x, None, "")?;
+    let slice_size = {
+        let y = op.output(0)?;
+        shape(scope, y, None, "")?
+    };
+    let before = expand_dims(scope, begin, 1, "")?;
+    let after = {
+        let d0 = sub(scope, input_shape, slice_size, "")?;
+        let d1 = sub(scope, d0, begin, "")?;
+        expand_dims(scope, d1, 1, "")?
+    };
+    let paddings = concat(scope, vec![before, after], 1, "")?;
+    Ok(vec![Some(pad(scope, grad, paddings, "")?), None, None])
+}
+
+fn pad_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    // slice the gradient where the input was placed
+    let grad = single_grad(grads)?;
+    let x = op.input(0);
+    let mut paddings = op.input(1);
+    if paddings.dtype != DataType::Int32 {
+        paddings = cast(scope, paddings, DataType::Int32, "")?;
+    }
+    let begin = {
+        let r = rank(scope, x, "")?;
+        // the first column of the paddings
+        let column_size = {
+            let r = expand_dims(scope, r, 0, "")?;
+            let one = scope.constant(&[1_i32], &[1] as &[i32], "")?;
+            concat(scope, vec![r, one.into()], 0, "")?
+        };
+        let before = slice(scope, paddings, &[0_i32, 0] as &[i32], column_size, "")?;
+        reshape(scope, before, &[-1_i32] as &[i32], "")?
+    };
+    let input_shape = shape(scope, x, None, "")?;
+    Ok(vec![Some(slice(scope, grad, begin, input_shape, "")?), None])
+}
+
+fn dynamic_stitch_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    // the inputs are the list of indices followed by the list of data
+    let grad = single_grad(grads)?;
+    let num_values = op.num_inputs() / 2;
+    let mut in_grads = vec![None; num_values];
+    for i in 0..num_values {
+        let mut indices = op.input(i);
+        if indices.dtype != DataType::Int32 {
+            indices = cast(scope, indices, DataType::Int32, "")?;
+        }
+        in_grads.push(Some(gather(scope, grad, indices, "")?));
+    }
+    Ok(in_grads)
+}
+
+fn unsorted_segment_sum_grad(
+    scope: &mut Scope,
+    op: &GradOp,
+    grads: &[Option<Tensor>],
+) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    Ok(vec![Some(gather(scope, grad, op.input(1), "")?), None, None])
+}
+
+fn switch_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    // only the branch which was taken receives a gradient which is not dead,
+    // the merge forwards it
+    let mut branch_grads = Vec::with_capacity(2);
+    for (idx, grad) in grads.iter().enumerate().take(2) {
+        branch_grads.push(match *grad {
+            Some(grad) => grad,
+            None => {
+                let output = op.output(idx)?;
+                zeros_like(scope, output, "")?
+            }
+        });
+    }
+    if branch_grads.len() < 2 {
+        return Err(Error::from(ErrorKind::InvalidArgument(
+            "expected the gradients of the two outputs of the switch".to_owned(),
+        )));
+    }
+    let (grad, _) = merge(scope, branch_grads, "")?;
+    Ok(vec![Some(grad), None])
+}
+
+fn merge_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    // route the gradient back to the input which was forwarded
+    let grad = single_grad(grads)?;
+    let value_index = op.output(1)?;
+    let mut in_grads = Vec::with_capacity(op.num_inputs());
+    for i in 0..op.num_inputs() {
+        let idx = scope.constant(&[i as i32], &[] as &[i32], "")?;
+        let pred = equal(scope, value_index, idx, "")?;
+        let (_, taken) = switch(scope, grad, pred, "")?;
+        in_grads.push(Some(taken));
+    }
+    Ok(in_grads)
+}
+
+fn reshape_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let input_shape = shape(scope, op.input(0), None, "")?;
+    let mut in_grads = vec![Some(reshape(scope, grad, input_shape, "")?)];
+    // the shape or axis argument (if any) is not differentiable
+    in_grads.resize(op.num_inputs(), None);
+    Ok(in_grads)
+}
+
+fn transpose_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let perm = invert_permutation(scope, op.input(1), "")?;
+    Ok(vec![Some(transpose(scope, grad, Some(perm), "")?), None])
+}
+
+fn concat_v2_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let num_values = op.num_inputs() - 1;
+    if num_values == 1 {
+        return Ok(vec![Some(grad), None]);
+    }
+    let mut axis = op.input(num_values);
+    if axis.dtype != DataType::Int32 {
+        axis = cast(scope, axis, DataType::Int32, "")?;
+    }
+    let mut sizes = Vec::with_capacity(num_values);
+    for i in 0..num_values {
+        sizes.push(shape(scope, op.input(i), None, "")?);
+    }
+    let input_rank = size(scope, sizes[0], "")?;
+    let axis = floor_mod(scope, axis, input_rank, "")?;
+    let offsets = concat_offset(scope, axis, sizes.clone(), "")?;
+    let mut in_grads = Vec::with_capacity(num_values + 1);
+    for (begin, value_size) in offsets.into_iter().zip(sizes) {
+        in_grads.push(Some(slice(scope, grad, begin, value_size, "")?));
+    }
+    in_grads.push(None);
+    Ok(in_grads)
+}
+
+fn gather_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+    let grad = single_grad(grads)?;
+    let (params, indices) = (op.input(0), op.input(1));
+    let params_shape = shape(scope, params, None, "")?;
+    let indices_size = {
+        let s = size(scope, indices, "")?;
+        expand_dims(scope, s, 0, "")?
+    };
+    let outer_dims = slice(scope, params_shape, &[1_i32] as &[i32], &[-1_i32] as &[i32], "")?;
+    let values_shape = concat(scope, vec![indices_size, outer_dims], 0, "")?;
+    let values = reshape(scope, grad, values_shape, "")?;
+    let indices = reshape(scope, indices, indices_size, "")?;
+    let first = scope.constant(&[0_i32], &[] as &[i32], "")?;
+    let num_segments = gather(scope, params_shape, first, "")?;
+    Ok(vec![
+        Some(unsorted_segment_sum(scope, values, indices, num_segments, "")?),
+        None,
+    ])
+}
+
+#[test]
+#[cfg(test)]
+fn test_gradients() {
+    let mut context = Scope::new();
+    let x = context.constant(&[2_f32], &[] as &[i32], "x").unwrap();
+    let c = context.constant(&[3_f32], &[] as &[i32], "c").unwrap();
+    // y = x * x + 3 * x
+    let x2 = multiply(&mut context, x, x, "").unwrap();
+    let x3 = multiply(&mut context, c, x, "").unwrap();
+    let y = add(&mut context, x2, x3, "").unwrap();
+    let grads = gradients(&mut context, &[y], &[x.into()], None).unwrap();
+    let op = grads[0].unwrap();
+
+    // tensors from other scopes are not found
+    let mut other = Scope::new();
+    let z = other.constant(&[2_f32], &[] as &[i32], "z").unwrap();
+    assert!(gradients(&mut context, &[y], &[z.into()], None).is_err());
+
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Float] == [7_f32]});
+}
+
+#[test]
+#[cfg(test)]
+fn test_gradients_broadcast_sum() {
+    let mut context = Scope::new();
+    let x = context.constant(&[1_f32, 2., 3., 4.], &[2, 2], "x").unwrap();
+    let b = context.constant(&[1_f32, 1.], &[2], "b").unwrap();
+    let z = add(&mut context, x, b, "").unwrap();
+    let y = reduce_sum(&mut context, z, &[1], false, "").unwrap();
+    let grads = gradients(&mut context, &[y], &[x.into(), b.into()], None).unwrap();
+    let (gx, gb) = (grads[0].unwrap(), grads[1].unwrap());
+    let results = test_suite!(run_op: [gx, gb]; context, input: {});
+    test_suite!(results; assert: {[0;Float] == [1_f32, 1., 1., 1.], [1;Float] == [2_f32, 2.]});
+}
+
+/// Compares the symbolic gradient of `sum(f(x)^2)` w.r.t. `x` with central finite differences.
+#[cfg(test)]
+fn check_gradient<F>(values: &[f64], dims: &[i32], f: F)
+where
+    F: Fn(&mut Scope, Tensor) -> Result<Tensor>,
+{
+    let eval = |values: &[f64], symbolic: bool| -> Vec<f64> {
+        let mut context = Scope::new();
+        let x: Tensor = context.constant(values, dims, "x").unwrap().into();
+        let y = f(&mut context, x).unwrap();
+        let loss = {
+            let sq = multiply(&mut context, y, y, "").unwrap();
+            let r = rank(&mut context, sq, "").unwrap();
+            let axes = range(&mut context, 0_i32, r, 1_i32, "").unwrap();
+            reduce_sum_dynamic(&mut context, sq, axes, false, "").unwrap()
+        };
+        let op = if symbolic {
+            gradients(&mut context, &[loss], &[x], None).unwrap()[0].unwrap()
+        } else {
+            loss
+        };
+        let results = test_suite!(run_op: [op]; context, input: {});
+        match results[0] {
+            TensorContent::Double(ref val) => val.to_vec(),
+            _ => panic!("wrong type specified for this test"),
+        }
+    };
+
+    let symbolic = eval(values, true);
+    assert_eq!(symbolic.len(), values.len());
+    let eps = 1e-6;
+    for i in 0..values.len() {
+        let mut plus = values.to_vec();
+        plus[i] += eps;
+        let mut minus = values.to_vec();
+        minus[i] -= eps;
+        let numeric = (eval(&plus, false)[0] - eval(&minus, false)[0]) / (2. * eps);
+        assert!(
+            (symbolic[i] - numeric).abs() <= 1e-5 * (1. + numeric.abs()),
+            "gradient {}: symbolic {} != numeric {}",
+            i,
+            symbolic[i],
+            numeric
+        );
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn test_numeric_gradients() {
+    use train::nn::{log_softmax, softmax};
+
+    let values = [0.5_f64, -1.2, 2., 0.3, 1.7, -0.4];
+    let weights = [1_f64, -2., 0.5, 3., 0.25, -1.];
+
+    check_gradient(&values, &[2, 3], |scope, x| {
+        let w = scope.constant(&weights, &[3, 2], "")?;
+        matmul(scope, x, w, false, false, false, false, false, false, "")
+    });
+    check_gradient(&values, &[2, 3], |scope, x| {
+        let w = scope.constant(&weights, &[2, 3], "")?;
+        matmul(scope, w, x, true, false, false, false, false, false, "")
+    });
+    check_gradient(&values, &[2, 3], |scope, x| {
+        let w = scope.constant(&weights, &[2, 3], "")?;
+        matmul(scope, x, w, false, true, false, false, false, false, "")
+    });
+    check_gradient(&values, &[2, 3], |scope, x| softmax(scope, x, -1_i32, ""));
+    check_gradient(&values, &[2, 3], |scope, x| log_softmax(scope, x, -1_i32, ""));
+    check_gradient(&values, &[2, 3], |scope, x| {
+        let c = scope.constant(&[1_f64, 2.], &[2, 1], "")?;
+        concat(scope, vec![c.into(), x, x], 1, "")
+    });
+    check_gradient(&values, &[3, 2], |scope, x| {
+        let indices = scope.constant(&[2_i32, 0, 2], &[3], "")?;
+        gather(scope, x, indices, "")
+    });
+    check_gradient(&values, &[2, 3], |scope, x| {
+        let t = transpose(scope, x, Some(&[1_i32, 0] as &[i32]), "")?;
+        let w = scope.constant(&weights[..4], &[2, 2], "")?;
+        matmul(scope, t, w, false, false, false, false, false, false, "")
+    });
+    check_gradient(&values, &[2, 3], |scope, x| reduce_sum(scope, x, &[1_i32], false, ""));
+    check_gradient(&values, &[2, 3], |scope, x| reduce_sum(scope, x, &[0_i32], true, ""));
+    check_gradient(&values, &[2, 3], |scope, x| {
+        let b = scope.constant(&weights[..3], &[3], "")?;
+        bias_add(scope, x, b, None, "")
+    });
+    check_gradient(&values[..3], &[3], |scope, x| {
+        let v = scope.constant(&weights, &[2, 3], "")?;
+        bias_add(scope, v, x, None, "")
+    });
+}
+
+#[test]
+#[cfg(test)]
+fn test_numeric_gradients_extra_ops() {
+    let values = [0.5_f64, 1.2, 2., 0.3, 1.7, 0.4];
+
+    check_gradient(&values, &[2, 3], |scope, x| reduce_max(scope, x, &[1_i32], false, ""));
+    check_gradient(&values, &[2, 3], |scope, x| {
+        let c = scope.constant(&[1_f64, 0.1, 1.9], &[3], "")?;
+        maximum(scope, x, c, "")
+    });
+    check_gradient(&values, &[2, 3], |scope, x| {
+        let c = scope.constant(&[1_f64, 0.1, 1.9], &[3], "")?;
+        minimum(scope, c, x, "")
+    });
+    check_gradient(&values, &[2, 3], |scope, x| {
+        let c = scope.constant(&[1.5_f64, 2., 0.5], &[3], "")?;
+        pow(scope, x, c, "")
+    });
+    check_gradient(&values, &[2, 3], |scope, x| {
+        let c = scope.constant(&[1.5_f64], &[] as &[i32], "")?;
+        pow(scope, c, x, "")
+    });
+    check_gradient(&values, &[2, 3], |scope, x| {
+        slice(scope, x, &[0_i32, 1] as &[i32], &[2_i32, 2] as &[i32], "")
+    });
+    check_gradient(&values, &[2, 3], |scope, x| {
+        let paddings = scope.constant(&[1_i32, 0, 0, 2], &[2, 2], "")?;
+        let w = scope.constant(&[1_f64, 2., 3.], &[3, 1], "")?;
+        let p = pad(scope, x, paddings, "")?;
+        multiply(scope, p, w, "")
+    });
+    check_gradient(&values, &[2, 3], |scope, x| {
+        let t = tile(scope, x, &[2_i32, 1] as &[i32], "")?;
+        let w = scope.constant(&[1_f64, 2., 3., 4.], &[4, 1], "")?;
+        multiply(scope, t, w, "")
+    });
+    check_gradient(&values, &[2, 3], |scope, x| {
+        let s = sin(scope, x, "")?;
+        cos(scope, s, "")
+    });
+    check_gradient(&values, &[2, 3], |scope, x| {
+        let ids = scope.constant(&[1_i32, 0], &[2], "")?;
+        let num_segments = scope.constant(&[3_i32], &[] as &[i32], "")?;
+        let w = scope.constant(&[1_f64, 2., 3.], &[3, 1], "")?;
+        let sum = unsorted_segment_sum(scope, x, ids, num_segments, "")?;
+        multiply(scope, sum, w, "")
+    });
+}
+
+#[test]
+#[cfg(test)]
+fn test_cond_gradients() {
+    for &(a, expected) in &[(1_f64, 6_f64), (5., 4.)] {
+        let mut context = Scope::new();
+        let x = context.constant(&[3_f64], &[] as &[i32], "x").unwrap();
+        let a = context.constant(&[a], &[] as &[i32], "a").unwrap();
+        let pred = less(&mut context, a, x, "").unwrap();
+        // x * x if a < x else 4 * x
+        let true_fn = Box::new(move |scope: &mut Scope| -> Result<Vec<Tensor>> {
+            Ok(vec![multiply(scope, x, x, "")?])
+        });
+        let false_fn = Box::new(move |scope: &mut Scope| -> Result<Vec<Tensor>> {
+            let c = scope.constant(&[4_f64], &[] as &[i32], "")?;
+            Ok(vec![multiply(scope, c, x, "")?])
+        });
+        let y = cond(&mut context, pred, true_fn, false_fn, "").unwrap()[0];
+        let grads = gradients(&mut context, &[y], &[x.into()], None).unwrap();
+        let op = grads[0].unwrap();
+        let results = test_suite!(run_op: [op]; context, input: {});
+        test_suite!(results; assert: {[0;Double] == [expected]});
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn test_gradient_registry() {
+    // an op declared only in this test, so no gradient is registered for it
+    add_new_op!(Square,
+        constructor: [add_new_op!(UNARY CONSTRUCTOR: Square, Init: []);],
+        digest: [DEFAULT_DIGEST: Square, INPUT0],
+        extra_funcs: [],
+        extra_attr: [],
+        output: [Tensor],
+    );
+
+    fn square_grad(scope: &mut Scope, op: &GradOp, grads: &[Option<Tensor>]) -> Result<Vec<Option<Tensor>>> {
+        let grad = single_grad(grads)?;
+        let x = op.input(0);
+        let double = add(scope, x, x, "")?;
+        Ok(vec![Some(multiply(scope, grad, double, "")?)])
+    }
+
+    let mut context = Scope::new();
+    assert!(context.register_gradient("Mul", square_grad).is_err());
+    assert!(context.not_differentiable("StopGradient").is_err());
+
+    let x = context.constant(&[2_f32], &[] as &[i32], "x").unwrap();
+    let c = context.constant(&[3_f32], &[] as &[i32], "c").unwrap();
+    let x2 = context.install(Square::new(x.into(), "").unwrap()).unwrap();
+    match *gradients(&mut context, &[x2], &[x.into()], None).unwrap_err().kind() {
+        ErrorKind::InvalidArgument(_) => {}
+        ref err => panic!("unexpected error: {}", err),
+    }
+
+    context.register_gradient("Square", square_grad).unwrap();
+    let y0 = stop_gradient(&mut context, x, "").unwrap();
+    let grads = gradients(&mut context, &[y0], &[x.into()], None).unwrap();
+    assert!(grads[0].is_none());
+
+    // y = 3 * x^2 + stop_gradient(x)
+    let y1 = multiply(&mut context, c, x2, "").unwrap();
+    let grads = gradients(&mut context, &[y0, y1], &[x.into()], None).unwrap();
+    let op = grads[0].unwrap();
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Float] == [12_f32]});
+}
+
+#[test]
+#[cfg(test)]
+fn test_gradients_imported_ops() {
+    let graph_def = {
+        let mut root = Scope::new();
+        let x = root.constant(&[2_f32], &[] as &[i32], "x").unwrap();
+        multiply(&mut root, x, x, "y").unwrap();
+        let graph_def = root.graph.borrow().graph_def().unwrap();
+        graph_def
+    };
+
+    let mut context = Scope::new();
+    let imported = context.import_graph_def(&graph_def, "imported").unwrap();
+    let (x, y) = (imported["imported/x:0"], imported["imported/y:0"]);
+    // the imported ops have no inputs recorded, so the path to `x` can't be followed
+    match *gradients(&mut context, &[y], &[x.into()], None).unwrap_err().kind() {
+        ErrorKind::InvalidArgument(_) => {}
+        ref err => panic!("unexpected error: {}", err),
+    }
+
+    // `x` could be behind the op of `y`
+    let c = context.constant(&[3_f32], &[] as &[i32], "c").unwrap();
+    let z = multiply(&mut context, c, y, "").unwrap();
+    assert!(gradients(&mut context, &[z], &[y.into(), x.into()], None).is_err());
+
+    // ops without inputs, or the op of an x, don't need to be walked through
+    let grads = gradients(&mut context, &[z], &[y.into(), c.into()], None).unwrap();
+    let op = grads[0].unwrap();
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Float] == [3_f32]});
+}
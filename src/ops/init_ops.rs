@@ -57,7 +57,7 @@ where
     F: Float,
     TeS: ShapeSize,
 {
-    let scope = &mut context.name_scope("random_normal", None);
+    let scope = &mut context.try_name_scope("random_normal", None)?;
 
     let shape_tensor = scope.constant(shape, &[shape.len() as i64], "")?;
     let mean_tensor = scope.constant(&[mean], &[] as &[i64], "mean")?;
@@ -203,7 +203,7 @@ where
             let vals = vec![::Complex64::new(0., 0.); elem_num];
             context.constant(&vals, shape, "")
         }
-        _ => Err(Error::from(ErrorKind::Stub)),
+        _ => Err(Error::from(ErrorKind::UnknownDType(dtype))),
     }
 }
 
@@ -0,0 +1,167 @@
+//! Input/Output Operations.
+
+use super::*;
+
+///// RestoreV2 /////
+
+/// Restores a tensor from a V2 checkpoint.
+///
+/// ### Args
+/// * prefix: A scalar `Tensor` of type string. The prefix of a V2 checkpoint.
+/// * tensor_name: The name of the tensor to be restored.
+/// * shape_and_slice: The slice of the tensor to restore, empty to restore the whole tensor.
+/// * dtype: The type of the tensor to be restored.
+/// * name: A name for the operation (empty string slice for autogenerated name).
+///
+/// ### Returns
+/// * A `Tensor` of type `dtype`.
+pub fn restore_v2<Tx, S>(
+    context: &mut Scope,
+    prefix: Tx,
+    tensor_name: &str,
+    shape_and_slice: &str,
+    dtype: DataType,
+    name: S,
+) -> Result<Tensor>
+where
+    Tx: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    let prefix = prefix.into();
+    if prefix.dtype != DataType::String {
+        return Err(Error::from(ErrorKind::DTypeMismatch(
+            "RestoreV2 prefix".to_owned(),
+            vec![DataType::String],
+            prefix.dtype,
+        )));
+    }
+    let tensor_names = context.constant(&[tensor_name.to_owned()], &[1], "")?;
+    let shape_and_slices = context.constant(&[shape_and_slice.to_owned()], &[1], "")?;
+    context.install(
+        RestoreV2::new(prefix, tensor_names.into(), shape_and_slices.into(), dtype, name)?
+            .dtypes(&[dtype]),
+    )
+}
+
+add_new_op!(RestoreV2,
+    constructor: [
+        fn new<S: AsRef<Path>>(
+            prefix: Tensor,
+            tensor_names: Tensor,
+            shape_and_slices: Tensor,
+            output_type: DataType,
+            name: S,
+        ) -> Result<RestoreV2<'a>> {
+            Ok(
+                RestoreV2 {
+                    ident: NodeIdent::new(),
+                    elements: vec![prefix, tensor_names, shape_and_slices],
+                    name: generate_name!(is_none: name),
+                    attributes: vec![],
+                    input_lists: vec![],
+                    output_type,
+                },
+            )
+        }
+    ],
+    digest: [DEFAULT_DIGEST: RestoreV2, DTYPE_ATTR],
+    extra_funcs: [
+        fn dtypes(mut self, val: &'a [DataType]) -> Self {
+            self.attributes.push(("dtypes", true, Attribute::Type(val)));
+            self
+        }
+    ],
+    extra_attr: [output_type: DataType],
+    output: [Tensor],
+);
+
+
+///// SaveV2 /////
+
+/// Saves tensors in V2 checkpoint format.
+///
+/// By default, saves the named tensors in full. If the caller wishes to save specific
+/// slices of full tensors, "shape_and_slices" should be non-empty strings and
+/// correspondingly well-formed.
+///
+/// ### Args
+/// * prefix: A scalar `Tensor` of type string. Must have a single element. The prefix of
+///   the V2 checkpoint to which we write the tensors.
+/// * tensor_names: The names of the tensors to be saved.
+/// * shape_and_slices: The slice specs of the tensors to be saved. Empty strings
+///   indicate that they are non-partitioned tensors.
+/// * tensors: `N` tensors to save.
+/// * name: A name for the operation (empty string slice for autogenerated name).
+///
+/// ### Returns
+/// * The identifier of the created operation, which has no outputs but can be used as
+///   a control dependency.
+pub fn save_v2<Tx, S>(
+    context: &mut Scope,
+    prefix: Tx,
+    tensor_names: &[String],
+    shape_and_slices: &[String],
+    tensors: Vec<Tensor>,
+    name: S,
+) -> Result<NodeIdent>
+where
+    Tx: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    let prefix = prefix.into();
+    if prefix.dtype != DataType::String {
+        return Err(Error::from(ErrorKind::DTypeMismatch(
+            "SaveV2 prefix".to_owned(),
+            vec![DataType::String],
+            prefix.dtype,
+        )));
+    }
+    if tensor_names.len() != tensors.len() || shape_and_slices.len() != tensors.len() {
+        return Err(Error::from(ErrorKind::InvalidArgument(format!(
+            "SaveV2 got {} tensors, {} names and {} slices",
+            tensors.len(),
+            tensor_names.len(),
+            shape_and_slices.len()
+        ))));
+    }
+    let tensor_names = context.constant(tensor_names, &[tensors.len() as i64], "")?;
+    let shape_and_slices = context.constant(shape_and_slices, &[tensors.len() as i64], "")?;
+    context.install(SaveV2::new(
+        prefix,
+        tensor_names.into(),
+        shape_and_slices.into(),
+        tensors,
+        name,
+    )?)
+}
+
+add_new_op!(SaveV2,
+    constructor: [
+        fn new<S: AsRef<Path>>(
+            prefix: Tensor,
+            tensor_names: Tensor,
+            shape_and_slices: Tensor,
+            tensors: Vec<Tensor>,
+            name: S,
+        ) -> Result<SaveV2<'a>> {
+            Ok(
+                SaveV2 {
+                    ident: NodeIdent::new(),
+                    elements: vec![prefix, tensor_names, shape_and_slices],
+                    name: generate_name!(is_none: name),
+                    attributes: vec![],
+                    input_lists: vec![(3, tensors)],
+                },
+            )
+        }
+    ],
+    digest: [DIGEST:
+        fn digest(self, context: &mut Scope, op: OperationData) -> Result<Self::Outputs> {
+            add_new_op!(REGISTER_AS_OP: (self, context, op); SaveV2);
+            Ok(self.ident)
+        }
+    ],
+    extra_funcs: [],
+    extra_attr: [],
+    output: [NodeIdent],
+);
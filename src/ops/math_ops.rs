@@ -55,7 +55,11 @@ add_new_op!(AddN,
             let output_type = values[0].dtype;
             for x in &values {
                 if &x.dtype != &output_type {
-                    return Err(Error::from(ErrorKind::Stub));
+                    return Err(Error::from(ErrorKind::DTypeMismatch(
+                        "AddN values".to_owned(),
+                        vec![output_type],
+                        x.dtype,
+                    )));
                 }
             }
 
@@ -121,7 +125,10 @@ add_new_op!(Cast,
         fn new<S: AsRef<Path>>(x: Tensor, dst_type: &'a [DataType], name: S) -> Result<Cast<'a>> {
             if &x.dtype == &dst_type[0] {
                 // trivial cast
-                return Err(Error::from(ErrorKind::Stub));
+                return Err(Error::from(ErrorKind::InvalidArgument(format!(
+                    "trivial cast, the tensor is already of type {:?}",
+                    x.dtype
+                ))));
             }
             Ok(
                 Cast {
@@ -152,6 +159,42 @@ fn test_cast() {
 }
 
 
+///// Ceil /////
+
+/// Returns element-wise smallest integer not less than x.
+///
+/// ### Args
+/// * x: A Tensor. Must be one of the following types: half, float32, float64.
+/// * name: A name for the operation (empty string slice for autogenerated name).
+pub fn ceil<Tx, S>(context: &mut Scope, x: Tx, name: S) -> Result<Tensor>
+where
+    Tx: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    context.install(Ceil::new(x.into(), name)?)
+}
+
+add_new_op!(Ceil,
+    constructor: [
+        add_new_op!(UNARY CONSTRUCTOR: Ceil, Init: []);
+    ],
+    digest: [DEFAULT_DIGEST: Ceil, INPUT0],
+    extra_funcs: [],
+    extra_attr: [],
+    output: [Tensor],
+);
+
+#[test]
+#[cfg(test)]
+fn test_ceil() {
+    let mut context = Scope::new();
+    let x = context.constant(&[-1.5_f32, 2.5], &[2], "x").unwrap();
+    let op = ceil(&mut context, x, "").unwrap();
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Float] == [-1_f32, 3.]});
+}
+
+
 ///// Conj /////
 
 ///  Returns the complex conjugate of a complex number.
@@ -187,7 +230,7 @@ where
 {
     let x = x.into();
     if x.dtype.is_complex() {
-        let scope = &mut context.name_scope(name.as_ref(), Some("Conj".as_ref()));
+        let scope = &mut context.try_name_scope(name.as_ref(), Some("Conj".as_ref()))?;
         scope.install(Conj::new(x.into(), name)?)
     } else if x.dtype.is_floating() || x.dtype.is_integer() {
         Ok(x)
@@ -207,6 +250,78 @@ add_new_op!(Conj,
 );
 
 
+///// Cos /////
+
+/// Computes cos of x element-wise.
+///
+/// ### Args
+/// * x: A Tensor. Must be one of the following types: half, float32, float64, complex64, complex128.
+/// * name: A name for the operation (empty string slice for autogenerated name).
+pub fn cos<Tx, S>(context: &mut Scope, x: Tx, name: S) -> Result<Tensor>
+where
+    Tx: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    context.install(Cos::new(x.into(), name)?)
+}
+
+add_new_op!(Cos,
+    constructor: [
+        add_new_op!(UNARY CONSTRUCTOR: Cos, Init: []);
+    ],
+    digest: [DEFAULT_DIGEST: Cos, INPUT0],
+    extra_funcs: [],
+    extra_attr: [],
+    output: [Tensor],
+);
+
+#[test]
+#[cfg(test)]
+fn test_cos() {
+    let mut context = Scope::new();
+    let x = context.constant(&[0_f64], &[] as &[i32], "x").unwrap();
+    let op = cos(&mut context, x, "").unwrap();
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Double] == [1_f64]});
+}
+
+
+///// Sin /////
+
+/// Computes sin of x element-wise.
+///
+/// ### Args
+/// * x: A Tensor. Must be one of the following types: half, float32, float64, complex64, complex128.
+/// * name: A name for the operation (empty string slice for autogenerated name).
+pub fn sin<Tx, S>(context: &mut Scope, x: Tx, name: S) -> Result<Tensor>
+where
+    Tx: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    context.install(Sin::new(x.into(), name)?)
+}
+
+add_new_op!(Sin,
+    constructor: [
+        add_new_op!(UNARY CONSTRUCTOR: Sin, Init: []);
+    ],
+    digest: [DEFAULT_DIGEST: Sin, INPUT0],
+    extra_funcs: [],
+    extra_attr: [],
+    output: [Tensor],
+);
+
+#[test]
+#[cfg(test)]
+fn test_sin() {
+    let mut context = Scope::new();
+    let x = context.constant(&[0_f64], &[] as &[i32], "x").unwrap();
+    let op = sin(&mut context, x, "").unwrap();
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Double] == [0_f64]});
+}
+
+
 ///// Division /////
 
 /// Divide `x` by `y` element-wise.
@@ -321,6 +436,82 @@ fn test_exp() {
 }
 
 
+///// Floor /////
+
+/// Returns element-wise largest integer not greater than x.
+///
+/// ### Args
+/// * x: A Tensor. Must be one of the following types: half, float32, float64.
+/// * name: A name for the operation (empty string slice for autogenerated name).
+pub fn floor<Tx, S>(context: &mut Scope, x: Tx, name: S) -> Result<Tensor>
+where
+    Tx: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    context.install(Floor::new(x.into(), name)?)
+}
+
+add_new_op!(Floor,
+    constructor: [
+        add_new_op!(UNARY CONSTRUCTOR: Floor, Init: []);
+    ],
+    digest: [DEFAULT_DIGEST: Floor, INPUT0],
+    extra_funcs: [],
+    extra_attr: [],
+    output: [Tensor],
+);
+
+#[test]
+#[cfg(test)]
+fn test_floor() {
+    let mut context = Scope::new();
+    let x = context.constant(&[-1.5_f32, 2.5], &[2], "x").unwrap();
+    let op = floor(&mut context, x, "").unwrap();
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Float] == [-2_f32, 2.]});
+}
+
+
+///// FloorMod /////
+
+/// Returns element-wise remainder of division.
+///
+/// When `x < 0` xor `y < 0` is true, this follows Python semantics in that the result
+/// here is consistent with a flooring divide. E.g. `floor(x / y) * y + mod(x, y) = x`.
+///
+/// ### Args
+/// * x: A Tensor. Must be one of the following types: int32, int64, float32, float64.
+/// * y: A Tensor. Must have the same type as x.
+/// * name: A name for the operation (empty string slice for autogenerated name).
+pub fn floor_mod<Tx, Ty, S>(context: &mut Scope, x: Tx, y: Ty, name: S) -> Result<Tensor>
+where
+    Tx: Into<Tensor>,
+    Ty: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    context.install(FloorMod::new(x.into(), y.into(), name)?)
+}
+
+add_new_op!(FloorMod,
+    constructor: [add_new_op!(BIN CONSTRUCTOR: FloorMod, Init: []);],
+    digest: [DEFAULT_DIGEST: FloorMod, INPUT0],
+    extra_funcs: [], 
+    extra_attr: [],
+    output: [Tensor],
+);
+
+#[test]
+#[cfg(test)]
+fn test_floor_mod() {
+    let mut context = Scope::new();
+    let x = context.constant(&[-1_i32, 5], &[2], "x").unwrap();
+    let y = context.constant(&[3_i32, 3], &[2], "y").unwrap();
+    let op = floor_mod(&mut context, x, y, "").unwrap();
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Int32] == [2_i32, 2]});
+}
+
+
 ///// Greater /////
 
 /// Returns the truth value of (x > y) element-wise.
@@ -466,7 +657,7 @@ where
     Ty: Into<Tensor>,
     S: AsRef<Path>,
 {
-    let scope = &mut context.name_scope(name.as_ref(), Some("MatMul".as_ref()));
+    let scope = &mut context.try_name_scope(name.as_ref(), Some("MatMul".as_ref()))?;
     if transpose_a && adjoint_a {
         return Err(Error::from(
             "Only one of transpose_a and adjoint_a can be True."
@@ -654,6 +845,42 @@ fn test_multiply() {
 }
 
 
+///// Negative /////
+
+/// Computes numerical negative value element-wise.
+///
+/// I.e., \\(y = -x\\).
+///
+/// ### Args
+/// * x: A Tensor. Must be one of the following types: half, float32, float64, int32, int64, complex64, complex128.
+/// * name: A name for the operation (empty string slice for autogenerated name).
+pub fn negative<Tx, S>(context: &mut Scope, x: Tx, name: S) -> Result<Tensor>
+where
+    Tx: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    context.install(Neg::new(x.into(), name)?)
+}
+
+add_new_op!(Neg,
+    constructor: [add_new_op!(UNARY CONSTRUCTOR: Neg, Init: []);],
+    digest: [DEFAULT_DIGEST: Neg, INPUT0],
+    extra_funcs: [], 
+    extra_attr: [],
+    output: [Tensor],
+);
+
+#[test]
+#[cfg(test)]
+fn test_negative() {
+    let mut context = Scope::new();
+    let x = context.constant(&[4_i32, -2], &[2], "x").unwrap();
+    let op = negative(&mut context, x, "").unwrap();
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Int32] == [-4_i32, 2]});
+}
+
+
 ///// Less /////
 
 /// Returns the truth value of (x < y) element-wise.
@@ -754,7 +981,11 @@ where
 {
     let tensor = tensor.into();
     if tensor.dtype != DataType::Bool {
-        return Err(Error::from(ErrorKind::Stub));
+        return Err(Error::from(ErrorKind::DTypeMismatch(
+            "logical_not input".to_owned(),
+            vec![DataType::Bool],
+            tensor.dtype,
+        )));
     }
     context.install(LogicalNot::new(tensor, name)?)
 }
@@ -877,10 +1108,19 @@ where
 add_new_op!(All, 
     constructor: [
         fn new<S: AsRef<Path>>(input: Tensor, axis: Tensor, name: S) -> Result<All<'a>> {
-            if input.dtype != DataType::Bool ||
-               (axis.dtype != DataType::Int32 &&
-                axis.dtype != DataType::Int64) {
-                return Err(Error::from(ErrorKind::Stub));
+            if input.dtype != DataType::Bool {
+                return Err(Error::from(ErrorKind::DTypeMismatch(
+                    "All input".to_owned(),
+                    vec![DataType::Bool],
+                    input.dtype,
+                )));
+            }
+            if axis.dtype != DataType::Int32 && axis.dtype != DataType::Int64 {
+                return Err(Error::from(ErrorKind::DTypeMismatch(
+                    "All axis".to_owned(),
+                    vec![DataType::Int32, DataType::Int64],
+                    axis.dtype,
+                )));
             }
             Ok(
                 All {
@@ -969,7 +1209,7 @@ where
     S: AsRef<Path>,
     TeS: ShapeSize,
 {
-    let scope = &mut context.name_scope(name.as_ref(), Some("ReduceLogSumExp".as_ref()));
+    let scope = &mut context.try_name_scope(name.as_ref(), Some("ReduceLogSumExp".as_ref()))?;
     let input = input.into();
     if axis.len() == 0 {
         // TODO: infer reduction to scalar
@@ -1063,12 +1303,33 @@ where
     ))
 }
 
+/// Same as `reduce_sum`, but the dimensions to reduce are given as an (int32 or int64)
+/// tensor which may only be known at runtime.
+pub(crate) fn reduce_sum_dynamic<Tx, Ty, S>(
+    context: &mut Scope,
+    input: Tx,
+    axis: Ty,
+    keep_dims: bool,
+    name: S,
+) -> Result<Tensor>
+where
+    Tx: Into<Tensor>,
+    Ty: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    context.install(Sum::new(input.into(), axis.into(), name)?.keep_dims(&[keep_dims]))
+}
+
 add_new_op!(Sum, 
     constructor: [
         fn new<S: AsRef<Path>>(input: Tensor, axis: Tensor, name: S) -> Result<Sum<'a>> {
             if axis.dtype != DataType::Int32 &&
                axis.dtype != DataType::Int64 {
-                return Err(Error::from(ErrorKind::Stub));
+                return Err(Error::from(ErrorKind::DTypeMismatch(
+                    "Sum axis".to_owned(),
+                    vec![DataType::Int32, DataType::Int64],
+                    axis.dtype,
+                )));
             }
             Ok(
                 Sum {
@@ -1156,10 +1417,10 @@ where
     Td: TensorOps,
     S: AsRef<Path>,
 {
-    let scope = &mut context.name_scope(name.as_ref(), Some("Range".as_ref()));
+    let scope = &mut context.try_name_scope(name.as_ref(), Some("Range".as_ref()))?;
     let start = start.into_tensor(scope, "start");
-    let limit = start.into_tensor(scope, "limit");
-    let delta = start.into_tensor(scope, "delta");
+    let limit = limit.into_tensor(scope, "limit");
+    let delta = delta.into_tensor(scope, "delta");
     scope.install(Range::new(start, limit, delta, name)?)
 }
 
@@ -1228,7 +1489,11 @@ add_new_op!(Max,
         fn new<S: AsRef<Path>>(input: Tensor, axis: Tensor, name: S) -> Result<Max<'a>> {
             if axis.dtype != DataType::Int32 &&
                axis.dtype != DataType::Int64 {
-                return Err(Error::from(ErrorKind::Stub));
+                return Err(Error::from(ErrorKind::DTypeMismatch(
+                    "Max axis".to_owned(),
+                    vec![DataType::Int32, DataType::Int64],
+                    axis.dtype,
+                )));
             }
             Ok(
                 Max {
@@ -1283,7 +1548,7 @@ where
     Tx: Into<Tensor>,
     S: AsRef<Path>,
 {
-    let scope = &mut context.name_scope(name.as_ref(), Some("Tanh".as_ref()));
+    let scope = &mut context.try_name_scope(name.as_ref(), Some("Tanh".as_ref()))?;
     scope.install(Tanh::new(tensor.into(), name)?)
 }
 
@@ -1295,6 +1560,70 @@ add_new_op!(Tanh,
     output: [Tensor],
 );
 
+/// Computes the gradient for the tanh of `x` wrt its input.
+///
+/// Specifically, `grad = dy * (1 - y*y)`, where `y = tanh(x)`, and `dy`
+/// is the corresponding input gradient.
+pub(crate) fn tanh_grad<Ty, Tdy, S>(context: &mut Scope, y: Ty, dy: Tdy, name: S) -> Result<Tensor>
+where
+    Ty: Into<Tensor>,
+    Tdy: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    context.install(TanhGrad::new(y.into(), dy.into(), name)?)
+}
+
+add_new_op!(TanhGrad,
+    constructor: [add_new_op!(BIN CONSTRUCTOR: TanhGrad, Init: []);],
+    digest: [DEFAULT_DIGEST: TanhGrad, INPUT0],
+    extra_funcs: [], 
+    extra_attr: [],
+    output: [Tensor],
+);
+
+
+///// Maximum /////
+
+/// Returns the max of x and y (i.e. x > y ? x : y) element-wise.
+///
+/// __NOTE:__ Maximum supports broadcasting.
+///
+/// ### Args
+/// * x: A `Tensor`. Must be one of the following types: `half`, `float32`, `float64`, `int32`, `int64`.
+/// * y: A `Tensor`. Must have the same type as `x`.
+/// * name: A name for the operation (empty string slice for autogenerated name).
+///
+/// ### Returns
+/// A `Tensor`. Has the same type as `x`.
+pub fn maximum<Tx, Ty, S>(context: &mut Scope, x: Tx, y: Ty, name: S) -> Result<Tensor>
+where
+    Tx: Into<Tensor>,
+    Ty: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    context.install(Maximum::new(x.into(), y.into(), name)?)
+}
+
+add_new_op!(Maximum,
+    constructor: [add_new_op!(BIN CONSTRUCTOR: Maximum, Init: []);],
+    digest: [DEFAULT_DIGEST: Maximum, INPUT0],
+    extra_funcs: [],
+    extra_attr: [],
+    output: [Tensor],
+);
+
+#[test]
+#[cfg(test)]
+fn test_maximum() {
+    let mut context = Scope::new();
+    let x = context.constant(&[4_i32, 3], &[2] as &[i32], "x").unwrap();
+    let y = context.constant(&[2_i32, 6], &[2] as &[i32], "y").unwrap();
+
+    let op = maximum(&mut context, x, y, "").unwrap();
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Int32] == [4_i32, 6]});
+}
+
 
 ///// Minimum /////
 
@@ -254,6 +254,18 @@ macro_rules! add_new_op {
         {
             &self.attributes
         }
+
+        fn take_tensor_attributes(&mut self)
+            -> Vec<(String, bool, Vec<::framework::TensorContent>)>
+        {
+            let mut tensors = vec![];
+            for &mut (name, is_list, ref mut attribute) in self.attributes.iter_mut() {
+                if let Attribute::Tensor(ref mut val) = *attribute {
+                    tensors.push((name.to_owned(), is_list, ::std::mem::replace(val, vec![])));
+                }
+            }
+            tensors
+        }
     };
     // DataType inference:
     (INPUT0 $s:ident) => ($s.elements[0].dtype);
@@ -306,9 +318,15 @@ pub use self::array_ops::*;
 pub(crate) mod control_flow_ops;
 pub use self::control_flow_ops::*;
 
+pub(crate) mod gradients;
+pub use self::gradients::*;
+
 pub(crate) mod init_ops;
 pub use self::init_ops::*;
 
+pub(crate) mod io_ops;
+pub use self::io_ops::*;
+
 pub(crate) mod math_ops;
 pub use self::math_ops::*;
 
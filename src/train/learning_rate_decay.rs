@@ -0,0 +1,598 @@
+//! Various learning rate decay functions.
+//!
+//! All the functions take a global step `Tensor` (usually a non-trainable variable
+//! incremented on each training step) and return a learning rate `Tensor` which
+//! can be passed to any of the optimizers.
+
+use std::cmp::Ordering;
+use std::f64::consts::PI;
+use std::path::Path;
+
+use super::*;
+use ops::{array_ops, math_ops};
+
+/// Returns the learning rate as a `Tensor` and the global step cast to its type.
+fn prepare<Tl, Ts>(scope: &mut Scope, learning_rate: Tl, global_step: Ts) -> Result<(Tensor, Tensor)>
+where
+    Tl: Into<Tensor>,
+    Ts: Into<Tensor>,
+{
+    let learning_rate = learning_rate.into();
+    match learning_rate.dtype {
+        DataType::Float | DataType::Double => {}
+        dtype => {
+            return Err(Error::from(ErrorKind::DTypeMismatch(
+                "learning rate".to_owned(),
+                vec![DataType::Float, DataType::Double],
+                dtype,
+            )))
+        }
+    }
+    let global_step = math_ops::cast(scope, global_step, learning_rate.dtype, "")?;
+    Ok((learning_rate, global_step))
+}
+
+fn check_decay_steps(decay_steps: u32) -> Result<()> {
+    if decay_steps == 0 {
+        Err(Error::from(ErrorKind::InvalidArgument(
+            "decay steps must be greater than zero".to_owned(),
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+
+///// Exponential decay /////
+
+/// Applies exponential decay to the learning rate.
+///
+/// The decayed learning rate is computed as:
+///
+/// ```text
+/// decayed_learning_rate = learning_rate * decay_rate ^ (global_step / decay_steps)
+/// ```
+///
+/// If `staircase` is `true` then `global_step / decay_steps` is an integer division
+/// and the decayed learning rate follows a staircase function.
+///
+/// ### Args
+/// * learning_rate: A scalar float `Tensor`. The initial learning rate.
+/// * global_step: A scalar `Tensor`. Global step to use for the decay computation.
+/// * decay_steps: Must be positive.
+/// * decay_rate: The decay rate.
+/// * staircase: If `true` decay the learning rate at discrete intervals.
+/// * name: A name for the operation (empty string slice for autogenerated name).
+///
+/// ### Returns
+/// A scalar `Tensor` of the same type as `learning_rate`. The decayed learning rate.
+pub fn exponential_decay<Tl, Ts, S>(
+    scope: &mut Scope,
+    learning_rate: Tl,
+    global_step: Ts,
+    decay_steps: u32,
+    decay_rate: f64,
+    staircase: bool,
+    name: S,
+) -> Result<Tensor>
+where
+    Tl: Into<Tensor>,
+    Ts: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    check_decay_steps(decay_steps)?;
+    let scope = &mut scope.try_name_scope(name.as_ref(), Some("ExponentialDecay".as_ref()))?;
+    let (learning_rate, global_step) = prepare(scope, learning_rate, global_step)?;
+    let dtype = learning_rate.dtype;
+
+    let decay_steps = scalar(scope, f64::from(decay_steps), dtype)?;
+    let decay_rate = scalar(scope, decay_rate, dtype)?;
+    let mut p = math_ops::divide(scope, global_step, decay_steps, "")?;
+    if staircase {
+        p = math_ops::floor(scope, p, "")?;
+    }
+    let decay = math_ops::pow(scope, decay_rate, p, "")?;
+    math_ops::multiply(scope, learning_rate, decay, "")
+}
+
+
+///// Inverse time decay /////
+
+/// Applies inverse time decay to the initial learning rate.
+///
+/// The decayed learning rate is computed as:
+///
+/// ```text
+/// decayed_learning_rate = learning_rate / (1 + decay_rate * global_step / decay_steps)
+/// ```
+///
+/// If `staircase` is `true` then `global_step / decay_steps` is an integer division.
+///
+/// ### Args
+/// * learning_rate: A scalar float `Tensor`. The initial learning rate.
+/// * global_step: A scalar `Tensor`. Global step to use for the decay computation.
+/// * decay_steps: How often to apply decay. Must be positive.
+/// * decay_rate: The decay rate.
+/// * staircase: If `true` apply decay in a discrete staircase, as opposed to continuous, fashion.
+/// * name: A name for the operation (empty string slice for autogenerated name).
+///
+/// ### Returns
+/// A scalar `Tensor` of the same type as `learning_rate`. The decayed learning rate.
+pub fn inverse_time_decay<Tl, Ts, S>(
+    scope: &mut Scope,
+    learning_rate: Tl,
+    global_step: Ts,
+    decay_steps: u32,
+    decay_rate: f64,
+    staircase: bool,
+    name: S,
+) -> Result<Tensor>
+where
+    Tl: Into<Tensor>,
+    Ts: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    check_decay_steps(decay_steps)?;
+    let scope = &mut scope.try_name_scope(name.as_ref(), Some("InverseTimeDecay".as_ref()))?;
+    let (learning_rate, global_step) = prepare(scope, learning_rate, global_step)?;
+    let dtype = learning_rate.dtype;
+
+    let decay_steps = scalar(scope, f64::from(decay_steps), dtype)?;
+    let decay_rate = scalar(scope, decay_rate, dtype)?;
+    let one = scalar(scope, 1.0, dtype)?;
+    let mut p = math_ops::divide(scope, global_step, decay_steps, "")?;
+    if staircase {
+        p = math_ops::floor(scope, p, "")?;
+    }
+    let denom = math_ops::multiply(scope, decay_rate, p, "")?;
+    let denom = math_ops::add(scope, one, denom, "")?;
+    math_ops::divide(scope, learning_rate, denom, "")
+}
+
+
+///// Piecewise constant /////
+
+/// Piecewise constant from boundaries and interval values.
+///
+/// Returns `values[0]` when `x <= boundaries[0]`, `values[i]` when
+/// `boundaries[i - 1] < x <= boundaries[i]` and `values[boundaries.len()]` when
+/// `x > boundaries[boundaries.len() - 1]`.
+///
+/// For example, to use a learning rate of 1.0 for the first 100000 steps, 0.5 for
+/// steps 100001 to 110000, and 0.1 for any additional steps:
+///
+/// ```ignore
+/// let values = [
+///     scope.constant(&[1.0_f32], &[] as &[i32], "")?,
+///     scope.constant(&[0.5_f32], &[] as &[i32], "")?,
+///     scope.constant(&[0.1_f32], &[] as &[i32], "")?,
+/// ];
+/// let lr = piecewise_constant(scope, global_step, &[100000., 110000.], &values, "")?;
+/// ```
+///
+/// ### Args
+/// * x: A scalar `Tensor` of type `float32`, `float64`, `int32` or `int64`.
+/// * boundaries: Strictly increasing boundaries, cast to the type of `x`.
+/// * values: Scalar tensors of the same type which specify the values for the intervals
+///   defined by `boundaries`. Must have one more element than `boundaries`.
+/// * name: A name for the operation (empty string slice for autogenerated name).
+///
+/// ### Returns
+/// A scalar `Tensor` with the type of `values`.
+pub fn piecewise_constant<Tx, Tv, S>(
+    scope: &mut Scope,
+    x: Tx,
+    boundaries: &[f64],
+    values: &[Tv],
+    name: S,
+) -> Result<Tensor>
+where
+    Tx: Into<Tensor>,
+    Tv: Into<Tensor> + Copy,
+    S: AsRef<Path>,
+{
+    if values.len() != boundaries.len() + 1 {
+        return Err(Error::from(ErrorKind::InvalidArgument(format!(
+            "expected {} values for {} boundaries, found {}",
+            boundaries.len() + 1,
+            boundaries.len(),
+            values.len()
+        ))));
+    }
+    if boundaries.windows(2).any(|pair| pair[0].partial_cmp(&pair[1]) != Some(Ordering::Less)) {
+        return Err(Error::from(ErrorKind::InvalidArgument(format!(
+            "the boundaries must be increasing, found {:?}",
+            boundaries
+        ))));
+    }
+    let values: Vec<Tensor> = values.iter().map(|x| (*x).into()).collect();
+    if let Some(value) = values.iter().find(|v| v.dtype != values[0].dtype) {
+        return Err(Error::from(ErrorKind::DTypeMismatch(
+            "piecewise_constant values".to_owned(),
+            vec![values[0].dtype],
+            value.dtype,
+        )));
+    }
+    let x = x.into();
+    let scope = &mut scope.try_name_scope(name.as_ref(), Some("PiecewiseConstant".as_ref()))?;
+
+    let mut result = values[0];
+    for (boundary, value) in boundaries.iter().zip(values.into_iter().skip(1)) {
+        let boundary = scalar(scope, *boundary, x.dtype)?;
+        let pred = math_ops::greater(scope, x, boundary, "")?;
+        result = array_ops::where_cond(scope, pred, Some(value), Some(result), "")?;
+    }
+    Ok(result)
+}
+
+
+///// Polynomial decay /////
+
+/// Applies a polynomial decay to the learning rate.
+///
+/// The decayed learning rate is computed as:
+///
+/// ```text
+/// global_step = min(global_step, decay_steps)
+/// decayed_learning_rate = (learning_rate - end_learning_rate) *
+///                         (1 - global_step / decay_steps) ^ (power) +
+///                         end_learning_rate
+/// ```
+///
+/// If `cycle` is `true` then a multiple of `decay_steps` is used instead, the first one
+/// which is bigger than `global_step`:
+///
+/// ```text
+/// decay_steps = decay_steps * max(1, ceil(global_step / decay_steps))
+/// ```
+///
+/// ### Args
+/// * learning_rate: A scalar float `Tensor`. The initial learning rate.
+/// * global_step: A scalar `Tensor`. Global step to use for the decay computation.
+/// * decay_steps: Must be positive.
+/// * end_learning_rate: The minimal end learning rate.
+/// * power: The power of the polynomial.
+/// * cycle: Whether or not it should cycle beyond `decay_steps`.
+/// * name: A name for the operation (empty string slice for autogenerated name).
+///
+/// ### Returns
+/// A scalar `Tensor` of the same type as `learning_rate`. The decayed learning rate.
+pub fn polynomial_decay<Tl, Ts, S>(
+    scope: &mut Scope,
+    learning_rate: Tl,
+    global_step: Ts,
+    decay_steps: u32,
+    end_learning_rate: f64,
+    power: f64,
+    cycle: bool,
+    name: S,
+) -> Result<Tensor>
+where
+    Tl: Into<Tensor>,
+    Ts: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    check_decay_steps(decay_steps)?;
+    let scope = &mut scope.try_name_scope(name.as_ref(), Some("PolynomialDecay".as_ref()))?;
+    let (learning_rate, mut global_step) = prepare(scope, learning_rate, global_step)?;
+    let dtype = learning_rate.dtype;
+
+    let mut decay_steps = scalar(scope, f64::from(decay_steps), dtype)?;
+    let end_learning_rate = scalar(scope, end_learning_rate, dtype)?;
+    let power = scalar(scope, power, dtype)?;
+    let one = scalar(scope, 1.0, dtype)?;
+    if cycle {
+        // find the first multiple of decay_steps bigger than global_step,
+        // at step 0 use decay_steps
+        let multiplier = math_ops::divide(scope, global_step, decay_steps, "")?;
+        let multiplier = math_ops::ceil(scope, multiplier, "")?;
+        let multiplier = math_ops::maximum(scope, multiplier, one, "")?;
+        decay_steps = math_ops::multiply(scope, decay_steps, multiplier, "")?;
+    } else {
+        // make sure that the global_step used is not bigger than decay_steps
+        global_step = math_ops::minimum(scope, global_step, decay_steps, "")?;
+    }
+
+    let p = math_ops::divide(scope, global_step, decay_steps, "")?;
+    let base = math_ops::sub(scope, one, p, "")?;
+    let decay = math_ops::pow(scope, base, power, "")?;
+    let diff = math_ops::sub(scope, learning_rate, end_learning_rate, "")?;
+    let decayed = math_ops::multiply(scope, diff, decay, "")?;
+    math_ops::add(scope, decayed, end_learning_rate, "")
+}
+
+
+///// Cosine decay /////
+
+/// Applies cosine decay to the learning rate.
+///
+/// See [Loshchilov & Hutter, ICLR2016](https://arxiv.org/abs/1608.03983), SGDR:
+/// Stochastic Gradient Descent with Warm Restarts. The decayed learning rate is
+/// computed as:
+///
+/// ```text
+/// global_step = min(global_step, decay_steps)
+/// cosine_decay = 0.5 * (1 + cos(pi * global_step / decay_steps))
+/// decayed = (1 - alpha) * cosine_decay + alpha
+/// decayed_learning_rate = learning_rate * decayed
+/// ```
+///
+/// ### Args
+/// * learning_rate: A scalar float `Tensor`. The initial learning rate.
+/// * global_step: A scalar `Tensor`. Global step to use for the decay computation.
+/// * decay_steps: Number of steps to decay over.
+/// * alpha: Minimum learning rate value as a fraction of `learning_rate`.
+/// * name: A name for the operation (empty string slice for autogenerated name).
+///
+/// ### Returns
+/// A scalar `Tensor` of the same type as `learning_rate`. The decayed learning rate.
+pub fn cosine_decay<Tl, Ts, S>(
+    scope: &mut Scope,
+    learning_rate: Tl,
+    global_step: Ts,
+    decay_steps: u32,
+    alpha: f64,
+    name: S,
+) -> Result<Tensor>
+where
+    Tl: Into<Tensor>,
+    Ts: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    check_decay_steps(decay_steps)?;
+    let scope = &mut scope.try_name_scope(name.as_ref(), Some("CosineDecay".as_ref()))?;
+    let (learning_rate, global_step) = prepare(scope, learning_rate, global_step)?;
+    let dtype = learning_rate.dtype;
+
+    let decay_steps = scalar(scope, f64::from(decay_steps), dtype)?;
+    let global_step = math_ops::minimum(scope, global_step, decay_steps, "")?;
+    let completed_fraction = math_ops::divide(scope, global_step, decay_steps, "")?;
+    let one = scalar(scope, 1.0, dtype)?;
+    let cosine_decayed = cosine_decayed(scope, completed_fraction, one, alpha)?;
+    math_ops::multiply(scope, learning_rate, cosine_decayed, "")
+}
+
+/// Applies cosine decay with restarts to the learning rate.
+///
+/// See [Loshchilov & Hutter, ICLR2016](https://arxiv.org/abs/1608.03983), SGDR:
+/// Stochastic Gradient Descent with Warm Restarts.
+///
+/// The learning rate multiplier first decays from 1 to `alpha` for `first_decay_steps`
+/// steps. Then, a warm restart is performed. Each new warm restart runs for `t_mul`
+/// times more steps and with `m_mul` times smaller initial learning rate.
+///
+/// ### Args
+/// * learning_rate: A scalar float `Tensor`. The initial learning rate.
+/// * global_step: A scalar `Tensor`. Global step to use for the decay computation.
+/// * first_decay_steps: Number of steps to decay over in the first period.
+/// * t_mul: Used to derive the number of iterations in the i-th period.
+/// * m_mul: Used to derive the initial learning rate of the i-th period.
+/// * alpha: Minimum learning rate value as a fraction of `learning_rate`.
+/// * name: A name for the operation (empty string slice for autogenerated name).
+///
+/// ### Returns
+/// A scalar `Tensor` of the same type as `learning_rate`. The decayed learning rate.
+pub fn cosine_decay_restarts<Tl, Ts, S>(
+    scope: &mut Scope,
+    learning_rate: Tl,
+    global_step: Ts,
+    first_decay_steps: u32,
+    t_mul: f64,
+    m_mul: f64,
+    alpha: f64,
+    name: S,
+) -> Result<Tensor>
+where
+    Tl: Into<Tensor>,
+    Ts: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    check_decay_steps(first_decay_steps)?;
+    let scope = &mut scope.try_name_scope(name.as_ref(), Some("SGDRDecay".as_ref()))?;
+    let (learning_rate, global_step) = prepare(scope, learning_rate, global_step)?;
+    let dtype = learning_rate.dtype;
+
+    let first_decay_steps = scalar(scope, f64::from(first_decay_steps), dtype)?;
+    let one = scalar(scope, 1.0, dtype)?;
+    let mut completed_fraction = math_ops::divide(scope, global_step, first_decay_steps, "")?;
+    let i_restart = if t_mul == 1.0 {
+        let i_restart = math_ops::floor(scope, completed_fraction, "")?;
+        completed_fraction = math_ops::sub(scope, completed_fraction, i_restart, "")?;
+        i_restart
+    } else {
+        // the length of the periods is a geometric series:
+        // sum_r = (1 - t_mul ^ i_restart) / (1 - t_mul)
+        let t_mul = scalar(scope, t_mul, dtype)?;
+        let one_minus_t_mul = math_ops::sub(scope, one, t_mul, "")?;
+        let i_restart = {
+            let x = math_ops::multiply(scope, completed_fraction, one_minus_t_mul, "")?;
+            let x = math_ops::sub(scope, one, x, "")?;
+            let x = math_ops::log(scope, x, "")?;
+            let log_t_mul = math_ops::log(scope, t_mul, "")?;
+            let x = math_ops::divide(scope, x, log_t_mul, "")?;
+            math_ops::floor(scope, x, "")?
+        };
+        let t_mul_pow = math_ops::pow(scope, t_mul, i_restart, "")?;
+        let sum_r = math_ops::sub(scope, one, t_mul_pow, "")?;
+        let sum_r = math_ops::divide(scope, sum_r, one_minus_t_mul, "")?;
+        completed_fraction = math_ops::sub(scope, completed_fraction, sum_r, "")?;
+        completed_fraction = math_ops::divide(scope, completed_fraction, t_mul_pow, "")?;
+        i_restart
+    };
+
+    let m_mul = scalar(scope, m_mul, dtype)?;
+    let m_fac = math_ops::pow(scope, m_mul, i_restart, "")?;
+    let cosine_decayed = cosine_decayed(scope, completed_fraction, m_fac, alpha)?;
+    math_ops::multiply(scope, learning_rate, cosine_decayed, "")
+}
+
+/// `(1 - alpha) * 0.5 * m_fac * (1 + cos(pi * completed_fraction)) + alpha`
+fn cosine_decayed(
+    scope: &mut Scope,
+    completed_fraction: Tensor,
+    m_fac: Tensor,
+    alpha: f64,
+) -> Result<Tensor> {
+    let dtype = completed_fraction.dtype;
+    let pi = scalar(scope, PI, dtype)?;
+    let half = scalar(scope, 0.5, dtype)?;
+    let one = scalar(scope, 1.0, dtype)?;
+    let x = math_ops::multiply(scope, pi, completed_fraction, "")?;
+    let x = math_ops::cos(scope, x, "")?;
+    let x = math_ops::add(scope, one, x, "")?;
+    let x = math_ops::multiply(scope, m_fac, x, "")?;
+    let cosine_decayed = math_ops::multiply(scope, half, x, "")?;
+
+    let alpha_t = scalar(scope, alpha, dtype)?;
+    let one_minus_alpha = scalar(scope, 1.0 - alpha, dtype)?;
+    let decayed = math_ops::multiply(scope, one_minus_alpha, cosine_decayed, "")?;
+    math_ops::add(scope, decayed, alpha_t, "")
+}
+
+#[test]
+#[cfg(test)]
+fn test_exponential_decay() {
+    let mut context = Scope::new();
+    let lr = context.constant(&[0.1_f32], &[] as &[i32], "lr").unwrap();
+    let step = context.constant(&[25_i64], &[] as &[i32], "step").unwrap();
+    let op = exponential_decay(&mut context, lr, step, 10, 0.5, true, "").unwrap();
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Float] == [0.025_f32]});
+}
+
+#[test]
+#[cfg(test)]
+fn test_piecewise_constant() {
+    let mut context = Scope::new();
+    let values = [
+        context.constant(&[1.0_f32], &[] as &[i32], "").unwrap(),
+        context.constant(&[0.5_f32], &[] as &[i32], "").unwrap(),
+        context.constant(&[0.1_f32], &[] as &[i32], "").unwrap(),
+    ];
+    let s0 = context.constant(&[10_i64], &[] as &[i32], "s0").unwrap();
+    let s1 = context.constant(&[15_i64], &[] as &[i32], "s1").unwrap();
+    let s2 = context.constant(&[21_i64], &[] as &[i32], "s2").unwrap();
+    let op0 = piecewise_constant(&mut context, s0, &[10., 20.], &values, "").unwrap();
+    let op1 = piecewise_constant(&mut context, s1, &[10., 20.], &values, "").unwrap();
+    let op2 = piecewise_constant(&mut context, s2, &[10., 20.], &values, "").unwrap();
+    let results = test_suite!(run_op: [op0, op1, op2]; context, input: {});
+    test_suite!(results; assert: {
+        [0;Float] == [1.0_f32],
+        [1;Float] == [0.5_f32],
+        [2;Float] == [0.1_f32]
+    });
+}
+
+#[test]
+#[cfg(test)]
+fn test_piecewise_constant_invalid() {
+    let mut context = Scope::new();
+    let values = [
+        context.constant(&[1.0_f32], &[] as &[i32], "").unwrap(),
+        context.constant(&[0.5_f32], &[] as &[i32], "").unwrap(),
+    ];
+    let x = context.constant(&[10_i64], &[] as &[i32], "x").unwrap();
+    let errors = vec![
+        piecewise_constant(&mut context, x, &[10., 20.], &values, "").unwrap_err(),
+        piecewise_constant(&mut context, x, &[20., 10., 30.], &[values[0]; 4], "").unwrap_err(),
+        piecewise_constant(&mut context, x, &[10., 10.], &[values[0]; 3], "").unwrap_err(),
+    ];
+    for err in errors {
+        match *err.kind() {
+            ErrorKind::InvalidArgument(_) => {}
+            ref err => panic!("unexpected error: {}", err),
+        }
+    }
+}
+
+/// Runs the scalar `Double` learning rates and checks their values.
+#[cfg(test)]
+fn check_learning_rates(context: &mut Scope, ops: Vec<Tensor>, expected: &[f64]) {
+    use client::ClientSession;
+
+    let mut session = ClientSession::new(context).unwrap();
+    session.fetch(ops);
+    let results = session.run(None).unwrap();
+    assert_eq!(results.len(), expected.len());
+    for (result, expected) in results.iter().zip(expected) {
+        match *result {
+            TensorContent::Double(ref val) => {
+                assert!((val[0] - expected).abs() < 1e-12, "{} != {}", val[0], expected)
+            }
+            _ => panic!("wrong type specified for this test"),
+        }
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn test_inverse_time_decay() {
+    let mut context = Scope::new();
+    let lr = context.constant(&[0.1_f64], &[] as &[i32], "lr").unwrap();
+    let step = context.constant(&[5_i64], &[] as &[i32], "step").unwrap();
+    let ops = vec![
+        inverse_time_decay(&mut context, lr, step, 10, 0.5, false, "").unwrap(),
+        inverse_time_decay(&mut context, lr, step, 10, 0.5, true, "").unwrap(),
+    ];
+    // 0.1 / (1 + 0.5 * 5 / 10), and with an integer division of the steps
+    check_learning_rates(&mut context, ops, &[0.08, 0.1]);
+}
+
+#[test]
+#[cfg(test)]
+fn test_polynomial_decay() {
+    let mut context = Scope::new();
+    let lr = context.constant(&[0.1_f64], &[] as &[i32], "lr").unwrap();
+    let s0 = context.constant(&[0_i64], &[] as &[i32], "s0").unwrap();
+    let s5 = context.constant(&[5_i64], &[] as &[i32], "s5").unwrap();
+    let s15 = context.constant(&[15_i64], &[] as &[i32], "s15").unwrap();
+    let ops = vec![
+        polynomial_decay(&mut context, lr, s5, 10, 0.01, 0.5, false, "").unwrap(),
+        polynomial_decay(&mut context, lr, s15, 10, 0.01, 0.5, false, "").unwrap(),
+        polynomial_decay(&mut context, lr, s0, 10, 0.01, 0.5, true, "").unwrap(),
+        polynomial_decay(&mut context, lr, s15, 10, 0.01, 0.5, true, "").unwrap(),
+    ];
+    // (0.1 - 0.01) * (1 - step / decay_steps) ^ 0.5 + 0.01, when cycling
+    // decay_steps is 20 at step 15
+    let expected = [0.09 * 0.5_f64.sqrt() + 0.01, 0.01, 0.1, 0.055];
+    check_learning_rates(&mut context, ops, &expected);
+}
+
+#[test]
+#[cfg(test)]
+fn test_cosine_decay() {
+    let mut context = Scope::new();
+    let lr = context.constant(&[0.1_f64], &[] as &[i32], "lr").unwrap();
+    let s0 = context.constant(&[0_i64], &[] as &[i32], "s0").unwrap();
+    let s5 = context.constant(&[5_i64], &[] as &[i32], "s5").unwrap();
+    let s20 = context.constant(&[20_i64], &[] as &[i32], "s20").unwrap();
+    let ops = vec![
+        cosine_decay(&mut context, lr, s0, 10, 0.1, "").unwrap(),
+        cosine_decay(&mut context, lr, s5, 10, 0.1, "").unwrap(),
+        cosine_decay(&mut context, lr, s20, 10, 0.1, "").unwrap(),
+    ];
+    // 0.1 * (0.9 * 0.5 * (1 + cos(pi * min(step, 10) / 10)) + 0.1)
+    check_learning_rates(&mut context, ops, &[0.1, 0.055, 0.01]);
+}
+
+#[test]
+#[cfg(test)]
+fn test_cosine_decay_restarts() {
+    let mut context = Scope::new();
+    let lr = context.constant(&[0.1_f64], &[] as &[i32], "lr").unwrap();
+    let s5 = context.constant(&[5_i64], &[] as &[i32], "s5").unwrap();
+    let s15 = context.constant(&[15_i64], &[] as &[i32], "s15").unwrap();
+    let ops = vec![
+        cosine_decay_restarts(&mut context, lr, s5, 10, 2., 0.5, 0., "").unwrap(),
+        cosine_decay_restarts(&mut context, lr, s15, 10, 2., 0.5, 0., "").unwrap(),
+        cosine_decay_restarts(&mut context, lr, s15, 10, 1., 0.5, 0., "").unwrap(),
+    ];
+    // at step 15 the second period (of 20 steps) started 5 steps ago, or the
+    // second period (of 10 steps) is half done when the periods are not extended
+    let expected = [
+        0.05,
+        0.1 * 0.5 * 0.5 * (1. + (PI * 0.25).cos()),
+        0.1 * 0.5 * 0.5,
+    ];
+    check_learning_rates(&mut context, ops, &expected);
+}
@@ -4,19 +4,36 @@ use super::*;
 use super::framework::*;
 use errors::*;
 
+mod learning_rate_decay;
+pub use self::learning_rate_decay::*;
+
 mod moving_averages;
 pub use self::moving_averages::*;
 
+mod optimizer;
+pub use self::optimizer::*;
+
+mod saver;
+pub use self::saver::*;
+
 mod slot_creator;
 use self::slot_creator::*;
 
+mod training_ops;
+
+mod training_util;
+pub use self::training_util::*;
+
 pub mod nn;
 
 fn validate_convnet_data_dormat(data_format: &str) -> Result<&'static str> {
     match data_format {
         "NHWC" => Ok("NHWC"),
         "NCHW" => Ok("NCHW"),
-        _ => Err(Error::from(ErrorKind::Stub)),
+        _ => Err(Error::from(ErrorKind::InvalidArgument(format!(
+            "unknown data format `{}`",
+            data_format
+        )))),
     }
 }
 
@@ -24,114 +41,21 @@ fn _validate_convnet_3d_data_dormat(data_format: &str) -> Result<&'static str> {
     match data_format {
         "NDHWC" => Ok("NDHWC"),
         "NCDHW" => Ok("NCDHW"),
-        _ => Err(Error::from(ErrorKind::Stub)),
-    }
-}
-
-/////////////////////////////////
-//   Pre-built train models    //
-/////////////////////////////////
-
-/*
-#[derive(Debug, Clone)]
-pub(crate) struct Optimizer;
-
-/*
-beta1: Option<f64>, // 0.9
-beta2: Option<f64>, // 0.999
-epsilon: Option<f64>, // 1e-08
-*/
-
-impl Optimizer {
-    pub fn new<V>(kind: Optimizers, learning_rate: V, use_locking: bool) -> Self
-        where V: Into<Tensor>
-    {
-    }
-
-    pub fn configure(&mut self) -> &mut OptimizerInterface {
-    }
-
-    pub fn composite_optimizer(optimizer1: Optimizer,
-                               optimizer2: Optimizer,
-                               switch: bool,
-                               use_locking: bool)
-                               -> Self {
-    }
-
-    pub fn compute_gradients(&mut self,
-                             loss: Tensor,
-                             var_list: Vec<Tensor>)
-                             -> Vec<(Option<Tensor>, Tensor)> {
-    }
-
-    pub fn apply_gradients(&mut self,
-                           grads_and_vars: Vec<(Tensor, Tensor)>,
-                           global_step: Tensor)
-                           -> Tensor {
-    }
-}
-
-pub(crate) trait OptimizerInterface {
-    fn add_param(&mut self, name: &str, value: f64) {
+        _ => Err(Error::from(ErrorKind::InvalidArgument(format!(
+            "unknown data format `{}`",
+            data_format
+        )))),
     }
 }
 
-pub enum Optimizers {
-    GradientDescent,
-    Adam,
-    LazyAdam,
-    Momentum,
-    Composite,
-}
-
-// GradientDescentOptimizer
-#[derive(Clone)]
-struct GradientDescentOptimizer;
-
-impl GradientDescentOptimizer {}
-
-
-// AdamOptimizer
-#[derive(Clone)]
-struct AdamOptimizer;
-
-impl AdamOptimizer {}
-
-
-// LazyAdamOptimizer
-#[derive(Clone)]
-struct LazyAdamOptimizer;
-
-impl LazyAdamOptimizer {}
-
-// MomentumOptimizer
-#[derive(Clone)]
-struct MomentumOptimizer;
-
-impl MomentumOptimizer {}
-
-
-// CompositeOptimizer
-#[derive(Clone)]
-struct CompositeOptimizer;
-
-impl CompositeOptimizer {}
-
-
-#[derive(Debug, Clone)]
-pub struct ExponentialDecay;
-
-impl ExponentialDecay {
-    pub fn new(base_rate: Tensor,
-               step_var: Tensor,
-               decay_steps: u32,
-               decay_base: f64,
-               staircase: bool)
-               -> ExponentialDecay {
-        ExponentialDecay
-    }
-
-    pub fn run(self) -> Result<Tensor> {
-    }
+/// Returns a scalar constant with the given value and type.
+fn scalar(scope: &mut Scope, value: f64, dtype: DataType) -> Result<Tensor> {
+    let c = match dtype {
+        DataType::Float => scope.constant(&[value as f32], &[] as &[i32], "")?,
+        DataType::Double => scope.constant(&[value], &[] as &[i32], "")?,
+        DataType::Int32 => scope.constant(&[value as i32], &[] as &[i32], "")?,
+        DataType::Int64 => scope.constant(&[value as i64], &[] as &[i32], "")?,
+        _ => return Err(Error::from(ErrorKind::UnknownDType(dtype))),
+    };
+    Ok(c.into())
 }
-*/
@@ -85,6 +85,28 @@ impl ExponentialMovingAverage {
         }
     }
 
+    /// Creates a new ExponentialMovingAverage object which uses the global step of the
+    /// graph as `num_updates`, the global step is created if it doesn't exist yet.
+    ///
+    /// See `new` for the description of the arguments.
+    pub fn with_global_step<Tx>(
+        scope: &mut Scope,
+        decay: Tx,
+        zero_debias: bool,
+        name: &str,
+    ) -> Result<ExponentialMovingAverage>
+    where
+        Tx: Into<Tensor>,
+    {
+        let global_step = scope.get_or_create_global_step()?;
+        Ok(ExponentialMovingAverage::new(
+            decay,
+            Some(global_step.into()),
+            zero_debias,
+            name,
+        ))
+    }
+
     /// Maintains moving averages of variables.
     ///
     /// `var_list` must be a list of `Tensor` or `Tensor` objects.  This method
@@ -94,9 +116,8 @@ impl ExponentialMovingAverage {
     /// For `Tensor` objects, the shadow variables are initialized to 0 and zero
     /// debiased (see docstring in `assign_moving_average` for more details).
     ///
-    /// ~~shadow variables are created with `trainable=False` and added to the
-    /// `GraphKeys.ALL_VARIABLES` collection.  They will be returned by calls to
-    /// `tf.global_variables()`.~~
+    /// Shadow variables are not trainable, they are returned by
+    /// `Scope::global_variables` but not by `Scope::trainable_variables`.
     ///
     /// Note that `apply()` can be called multiple times with different lists of
     /// variables.
@@ -109,19 +130,28 @@ impl ExponentialMovingAverage {
     ///   * Operation that updates the moving averages.
     ///
     /// ### Errors:
-    ///   * TypeError: If the arguments are not all float16, float32, or float64.
-    ///   * ValueError: If the moving average of one of the variables is already
+    ///   * DTypeMismatch: If the arguments are not all float32 or float64.
+    ///   * InvalidArgument: If the moving average of one of the variables is already
     ///     being computed.
     pub fn apply(&mut self, context: &mut Scope, var_list: &[Tensor]) -> Result<Group> {
         let mut zero_debias_true: HashSet<NodeIdent> = HashSet::new(); // set of vars to set to `zero_debias=True`
         for var in var_list {
             match var.dtype {
                 DataType::Float | DataType::Double => {}
-                _ => return Err(Error::from(ErrorKind::Stub)),
+                found => {
+                    return Err(Error::from(ErrorKind::DTypeMismatch(
+                        format!("moving average of `{}`", var.get_name(context)),
+                        vec![DataType::Float, DataType::Double],
+                        found,
+                    )))
+                }
             }
 
             if self.averages.keys().find(|&&x| x.ident == var.ident).is_some() {
-                return Err(Error::from(ErrorKind::Stub));
+                return Err(Error::from(ErrorKind::InvalidArgument(format!(
+                    "the moving average of `{}` is already being computed",
+                    var.get_name(context)
+                ))));
             }
 
             // For variables: to lower communication bandwidth across devices we keep
@@ -141,7 +171,7 @@ impl ExponentialMovingAverage {
             self.averages.insert(*var, avg);
         }
 
-        let scope = &mut context.name_scope(self.name.as_str(), None);
+        let scope = &mut context.try_name_scope(self.name.as_str(), None)?;
         let mut decay: Tensor = self.decay;
         if let Some(mut num_updates) = self.num_updates {
             num_updates = math_ops::cast(scope, num_updates, DataType::Float, "num_updates")?;
@@ -219,8 +249,8 @@ impl ExponentialMovingAverage {
     ///
     /// ### Args:
     ///   * moving_avg_variables: a list of variables that require to use of the
-    ///     moving variable name to be restored. ~~If empty, it will default to
-    ///     context.moving_average_variables() + context.trainable_variables()~~
+    ///     moving variable name to be restored. If empty, it will default to
+    ///     the trainable variables of the graph.
     ///
     /// ### Returns:
     ///   A map from restore_names to variables. The restore_name can be the
@@ -233,12 +263,14 @@ impl ExponentialMovingAverage {
     ) -> HashMap<String, Tensor> {
         let mut name_map: HashMap<String, Tensor> = HashMap::new();
 
-        if moving_avg_variables.len() == 0 {
-            // Include trainable variables and variables which have been explicitly
-            // added to the moving_average_variables collection.
-            // TODO: moving_avg_variables = variables.trainable_variables()
-            //       moving_avg_variables += variables.moving_average_variables()
-        }
+        let trainable;
+        let moving_avg_variables = if moving_avg_variables.is_empty() {
+            // TODO: moving_avg_variables += variables.moving_average_variables()
+            trainable = scope.trainable_variables();
+            &trainable
+        } else {
+            moving_avg_variables
+        };
 
         // Remove duplicates
         let moving_avg_variables: HashSet<Tensor> =
@@ -299,13 +331,13 @@ fn assign_moving_average(
     name: Option<&str>,
 ) -> Result<Tensor> {
     let scope = &mut if let Some(name) = name {
-                         scope.name_scope(name, None)
+                         scope.try_name_scope(name, None)?
                      } else {
-                         scope.name_scope("", Some("AssignMovingAvg"))
+                         scope.try_name_scope("", Some("AssignMovingAvg"))?
                      };
     // TODO: colocate_with(variable)
     let decay = {
-        let n = scope.constant(&[1], &[] as &[i32], "")?;
+        let n = scalar(scope, 1.0, decay.dtype)?;
         math_ops::sub(scope, n, decay, "decay")?
     };
     let update_delta = if zero_debias {
@@ -364,9 +396,11 @@ fn _zero_debias(
         scope.get_variable(Some(unbiased_var.dtype), Some(unbiased_var_shape), "biased")?;
     let local_step =
         scope.get_variable(Some(unbiased_var.dtype), Some(&[] as &[i64]), "local_step")?;
+    scope.set_not_trainable(&biased_var);
+    scope.set_not_trainable(&local_step);
 
     // constants:
-    let one = scope.constant(&[1_i32], &[] as &[i32], "")?;
+    let one = scalar(scope, 1.0, unbiased_var.dtype)?;
 
     // Get an update ops for both shadow variables.
     let update_biased = {
@@ -380,7 +414,7 @@ fn _zero_debias(
 
     // Compute the value of the delta to update the unbiased EMA. Make sure to
     // use the new values of the biased variable and the local step.
-    let scope = &mut scope.control_dependencies(&[update_biased, update_local_step]);
+    let scope = &mut scope.try_control_dependencies(&[update_biased, update_local_step])?;
     {
         // This function gets `1 - decay`, so use `1.0 - decay` in the exponent.
         let a = math_ops::sub(scope, one, *decay, "")?;
@@ -392,3 +426,30 @@ fn _zero_debias(
         math_ops::divide(scope, c, b, "unbiased_ema_delta")
     }
 }
+
+#[test]
+#[cfg(test)]
+fn test_moving_average_global_step() {
+    use client::ClientSession;
+
+    let mut context = Scope::new();
+    let x: Tensor = context.constant(&[10_f32], &[] as &[i32], "x").unwrap().into();
+    let decay = context.constant(&[0.9_f32], &[] as &[i32], "decay").unwrap();
+    let mut ema = ExponentialMovingAverage::with_global_step(&mut context, decay, false, "ema")
+        .unwrap();
+    let update = ema.apply(&mut context, &[x]).unwrap();
+    let average = *ema.average(&x).unwrap();
+
+    let global_step = context.global_step().unwrap();
+    assert!(!context.is_trainable(&global_step));
+    assert!(!context.is_trainable(&average));
+    assert!(context.trainable_variables().is_empty());
+
+    // at step 0 the decay is min(0.9, (1 + 0) / (10 + 0)) = 0.1
+    let mut session = ClientSession::new(&mut context).unwrap();
+    session.target(vec![update]);
+    session.run(None).unwrap();
+    session.prune();
+    let value = session.fetch_as::<f32, _>(average).unwrap()[0];
+    assert!((value - 9.).abs() < 1e-5);
+}
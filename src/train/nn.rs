@@ -63,7 +63,7 @@ where
     B: Into<Tensor>,
     S: AsRef<Path>,
 {
-    context.name_scope(name.as_ref(), Some("BiasAdd".as_ref()));
+    context.try_name_scope(name.as_ref(), Some("BiasAdd".as_ref()))?;
     let value = value.into();
     let bias = bias.into();
     let d_id: &mut [&str] = &mut [""];
@@ -88,6 +88,43 @@ add_new_op!(BiasAdd,
     output: [Tensor],
 );
 
+/// The backward operation for `bias_add` on the "bias" tensor.
+///
+/// It accumulates all the values from `out_backprop` into the feature dimension.
+/// For NHWC data format, the feature dimension is the last. For NCHW data format,
+/// the feature dimension is the third-to-last.
+pub(crate) fn bias_add_grad<Tx, S>(
+    context: &mut Scope,
+    out_backprop: Tx,
+    data_format: Option<&str>,
+    name: S,
+) -> Result<Tensor>
+where
+    Tx: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    let d_id: &mut [&str] = &mut [""];
+    let mut bias_add_grad = BiasAddGrad::new(out_backprop.into(), name)?;
+    if let Some(data_format) = data_format {
+        d_id[0] = validate_convnet_data_dormat(data_format)?;
+        bias_add_grad = bias_add_grad.data_format(&d_id);
+    }
+    context.install(bias_add_grad)
+}
+
+add_new_op!(BiasAddGrad,
+    constructor: [add_new_op!(UNARY CONSTRUCTOR: BiasAddGrad, Init: []);],
+    digest: [DEFAULT_DIGEST: BiasAddGrad, INPUT0],
+    extra_funcs: [
+        fn data_format(mut self, val: &'a [&'a str]) -> Self {
+            self.attributes.push(("data_format", false, Attribute::String(val)));
+            self
+        }
+    ], 
+    extra_attr: [],
+    output: [Tensor],
+);
+
 
 ///// LogSoftmax /////
 
@@ -159,6 +196,28 @@ add_new_op!(Relu,
     output: [Tensor],
 );
 
+/// Computes rectified linear gradients for a Relu operation.
+///
+/// Returns `gradients * (features > 0)`.
+pub(crate) fn relu_grad<Tg, F, S>(scope: &mut Scope, gradients: Tg, features: F, name: S) -> Result<Tensor>
+where
+    Tg: Into<Tensor>,
+    F: Into<Tensor>,
+    S: AsRef<Path>,
+{
+    scope.install(ReluGrad::new(gradients.into(), features.into(), name)?)
+}
+
+add_new_op!(ReluGrad, 
+    constructor: [
+        add_new_op!(BIN CONSTRUCTOR: ReluGrad, Init: []);
+    ],
+    digest: [DEFAULT_DIGEST: ReluGrad, INPUT0],
+    extra_funcs: [], 
+    extra_attr: [],
+    output: [Tensor],
+);
+
 
 ///// Softmax /////
 
@@ -247,7 +306,9 @@ fn softmax_helper(
     let ndims = if let Some(n) = shape.dims() {
         n as i32
     } else {
-        return Err(Error::from("shape of logits tensor must be defined for softmax operation."));
+        return Err(Error::from(ErrorKind::InvalidArgument(
+            "the rank of logits must be known for the softmax operation".to_owned(),
+        )));
     };
     let is_last_dim = dim == -1 || dim == ndims - 1;
     if (ndims == 2) && is_last_dim {
@@ -0,0 +1,569 @@
+//! Optimizers which compute gradients for a loss and apply them to the variables.
+
+use std::collections::HashMap;
+
+use super::*;
+use super::training_ops::*;
+use ops::control_flow_ops::Group;
+use ops::{math_ops, state_ops};
+
+/// Interface shared by all the optimizers.
+///
+/// You never call `apply_dense`, `create_slots` or `finish` directly, but use
+/// `minimize` (or `compute_gradients` and `apply_gradients` to process the gradients
+/// before applying them) on the implementors:
+///
+/// ```ignore
+/// let lr = scope.constant(&[0.1_f32], &[] as &[i32], "learning_rate")?;
+/// let mut opt = GradientDescentOptimizer::new(lr, false, "");
+/// let train_op = opt.minimize(scope, loss, &[w, b], Some(global_step))?;
+/// ```
+pub trait Optimizer {
+    /// Name of the scope where the update ops are created.
+    fn get_name(&self) -> &str;
+
+    /// Creates the slots (accumulators) needed for each of the variables in `var_list`.
+    fn create_slots(&mut self, scope: &mut Scope, var_list: &[Tensor]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Adds the ops to update `var` with the (dense) gradient `grad`.
+    fn apply_dense(&mut self, scope: &mut Scope, grad: Tensor, var: Tensor) -> Result<Tensor>;
+
+    /// Adds any op needed after applying all the updates and returns the ops to group.
+    fn finish(&mut self, scope: &mut Scope, update_ops: Vec<Tensor>) -> Result<Vec<Tensor>> {
+        Ok(update_ops)
+    }
+
+    /// Computes the gradients of `loss` for the variables in `var_list`, or for all the
+    /// trainable variables in the graph if `var_list` is empty.
+    ///
+    /// Returns a vector of (gradient, variable) pairs, the gradient is `None` if the
+    /// variable does not contribute to the loss.
+    fn compute_gradients(
+        &mut self,
+        scope: &mut Scope,
+        loss: Tensor,
+        var_list: &[Tensor],
+    ) -> Result<Vec<(Option<Tensor>, Tensor)>> {
+        let trainable: Vec<Tensor>;
+        let var_list = if var_list.is_empty() {
+            trainable = scope.trainable_variables().into_iter().map(Into::into).collect();
+            &trainable
+        } else {
+            var_list
+        };
+        if var_list.is_empty() {
+            return Err(Error::from(ErrorKind::InvalidArgument(
+                "no variables to optimize".to_owned(),
+            )));
+        }
+        let grads = ops::gradients(scope, &[loss], var_list, None)?;
+        Ok(grads.into_iter().zip(var_list.iter().cloned()).collect())
+    }
+
+    /// Applies the gradients to the variables.
+    ///
+    /// If `global_step` is provided it's incremented by one after the variables
+    /// have been updated, it must be the global step of the graph (see
+    /// `Scope::get_or_create_global_step`).
+    ///
+    /// Returns an op which applies all the updates when run.
+    fn apply_gradients(
+        &mut self,
+        scope: &mut Scope,
+        grads_and_vars: &[(Option<Tensor>, Tensor)],
+        global_step: Option<Tensor>,
+    ) -> Result<Group> {
+        if let Some(global_step) = global_step {
+            if scope.global_step().map(|var| var.get_ident()) != Some(global_step.get_ident()) {
+                return Err(Error::from(ErrorKind::InvalidArgument(
+                    "global_step is not the global step variable of the graph".to_owned(),
+                )));
+            }
+        }
+        let grads_and_vars: Vec<(Tensor, Tensor)> = grads_and_vars
+            .iter()
+            .filter_map(|&(grad, var)| grad.map(|grad| (grad, var)))
+            .collect();
+        if grads_and_vars.is_empty() {
+            return Err(Error::from(ErrorKind::InvalidArgument(
+                "no gradients provided for any variable".to_owned(),
+            )));
+        }
+        let var_list: Vec<Tensor> = grads_and_vars.iter().map(|&(_, var)| var).collect();
+        self.create_slots(scope, &var_list)?;
+
+        let name = self.get_name().to_owned();
+        let scope = &mut scope.try_name_scope(name.as_str(), None)?;
+        let mut update_ops = Vec::with_capacity(grads_and_vars.len());
+        for (grad, var) in grads_and_vars {
+            update_ops.push(self.apply_dense(scope, grad, var)?);
+        }
+        let mut update_ops = self.finish(scope, update_ops)?;
+        if global_step.is_some() {
+            let scope = &mut scope.try_control_dependencies(&update_ops)?;
+            update_ops.push(increment_global_step(scope, 1)?);
+        }
+        Group::new(scope, &update_ops, "")
+    }
+
+    /// Adds the ops to minimize `loss` by updating the variables in `var_list`.
+    ///
+    /// Simply combines calls to `compute_gradients()` and `apply_gradients()`.
+    fn minimize(
+        &mut self,
+        scope: &mut Scope,
+        loss: Tensor,
+        var_list: &[Tensor],
+        global_step: Option<Tensor>,
+    ) -> Result<Group> {
+        let grads_and_vars = self.compute_gradients(scope, loss, var_list)?;
+        self.apply_gradients(scope, &grads_and_vars, global_step)
+    }
+}
+
+/// Casts a hyperparameter to the type of the variable it's applied to, if necessary.
+fn cast_like(scope: &mut Scope, tensor: Tensor, var: &Tensor) -> Result<Tensor> {
+    if tensor.dtype == var.dtype {
+        Ok(tensor)
+    } else {
+        math_ops::cast(scope, tensor, var.dtype, "")
+    }
+}
+
+
+///// GradientDescent /////
+
+/// Optimizer that implements the gradient descent algorithm.
+#[derive(Debug, Clone)]
+pub struct GradientDescentOptimizer {
+    learning_rate: Tensor,
+    use_locking: bool,
+    name: String,
+}
+
+impl GradientDescentOptimizer {
+    /// Construct a new gradient descent optimizer.
+    ///
+    /// ### Args:
+    ///   * learning_rate: A scalar `Tensor`. The learning rate to use.
+    ///   * use_locking: If `true` use locks for update operations.
+    ///   * name: Name prefix for the operations created when applying gradients.
+    ///     Defaults to "GradientDescent" if empty.
+    pub fn new<Tx>(learning_rate: Tx, use_locking: bool, name: &str) -> GradientDescentOptimizer
+    where
+        Tx: Into<Tensor>,
+    {
+        GradientDescentOptimizer {
+            learning_rate: learning_rate.into(),
+            use_locking,
+            name: default_name(name, "GradientDescent"),
+        }
+    }
+}
+
+impl Optimizer for GradientDescentOptimizer {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn apply_dense(&mut self, scope: &mut Scope, grad: Tensor, var: Tensor) -> Result<Tensor> {
+        let lr = cast_like(scope, self.learning_rate, &var)?;
+        apply_gradient_descent(scope, var, lr, grad, self.use_locking, "")
+    }
+}
+
+
+///// Momentum /////
+
+/// Optimizer that implements the Momentum algorithm.
+///
+/// Keeps an "accumulation" slot of the same shape for each variable.
+#[derive(Debug, Clone)]
+pub struct MomentumOptimizer {
+    learning_rate: Tensor,
+    momentum: Tensor,
+    use_locking: bool,
+    use_nesterov: bool,
+    name: String,
+    accumulators: HashMap<Tensor, Variable>,
+}
+
+impl MomentumOptimizer {
+    /// Construct a new Momentum optimizer.
+    ///
+    /// ### Args:
+    ///   * learning_rate: A scalar `Tensor`. The learning rate.
+    ///   * momentum: A scalar `Tensor`. The momentum.
+    ///   * use_locking: If `true` use locks for update operations.
+    ///   * use_nesterov: If `true` use Nesterov Momentum.
+    ///     See [Sutskever et al., 2013](http://jmlr.org/proceedings/papers/v28/sutskever13.pdf).
+    ///   * name: Name prefix for the operations created when applying gradients.
+    ///     Defaults to "Momentum" if empty.
+    pub fn new<Tx, Ty>(
+        learning_rate: Tx,
+        momentum: Ty,
+        use_locking: bool,
+        use_nesterov: bool,
+        name: &str,
+    ) -> MomentumOptimizer
+    where
+        Tx: Into<Tensor>,
+        Ty: Into<Tensor>,
+    {
+        MomentumOptimizer {
+            learning_rate: learning_rate.into(),
+            momentum: momentum.into(),
+            use_locking,
+            use_nesterov,
+            name: default_name(name, "Momentum"),
+            accumulators: HashMap::new(),
+        }
+    }
+
+    /// Returns the accumulator slot created for `var`, if any.
+    pub fn get_slot(&self, var: &Tensor) -> Option<&Variable> {
+        self.accumulators.get(var)
+    }
+}
+
+impl Optimizer for MomentumOptimizer {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn create_slots(&mut self, scope: &mut Scope, var_list: &[Tensor]) -> Result<()> {
+        for var in var_list {
+            if !self.accumulators.contains_key(var) {
+                let accum = create_zeros_slot(scope, *var, "momentum", true)?;
+                self.accumulators.insert(*var, accum);
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_dense(&mut self, scope: &mut Scope, grad: Tensor, var: Tensor) -> Result<Tensor> {
+        let accum = self.accumulators[&var];
+        let lr = cast_like(scope, self.learning_rate, &var)?;
+        let momentum = cast_like(scope, self.momentum, &var)?;
+        apply_momentum(
+            scope,
+            var,
+            accum.into(),
+            lr,
+            grad,
+            momentum,
+            self.use_locking,
+            self.use_nesterov,
+            "",
+        )
+    }
+}
+
+
+///// Adam /////
+
+/// Optimizer that implements the Adam algorithm.
+///
+/// See [Kingma et al., 2014](http://arxiv.org/abs/1412.6980)
+/// ([pdf](http://arxiv.org/pdf/1412.6980.pdf)).
+///
+/// Keeps the "m" and "v" slots of the same shape for each variable, and the
+/// `beta1^t` and `beta2^t` accumulators shared by all the variables.
+#[derive(Debug, Clone)]
+pub struct AdamOptimizer {
+    learning_rate: Tensor,
+    beta1: Tensor,
+    beta2: Tensor,
+    epsilon: Tensor,
+    use_locking: bool,
+    name: String,
+    m: HashMap<Tensor, Variable>,
+    v: HashMap<Tensor, Variable>,
+    beta1_power: Option<Variable>,
+    beta2_power: Option<Variable>,
+}
+
+impl AdamOptimizer {
+    /// Construct a new Adam optimizer.
+    ///
+    /// Initialization:
+    ///
+    /// ```text
+    /// m_0 <- 0 (Initialize initial 1st moment vector)
+    /// v_0 <- 0 (Initialize initial 2nd moment vector)
+    /// t <- 0 (Initialize timestep)
+    /// ```
+    ///
+    /// The update rule for `variable` with gradient `g` is:
+    ///
+    /// ```text
+    /// t <- t + 1
+    /// lr_t <- learning_rate * sqrt(1 - beta2^t) / (1 - beta1^t)
+    ///
+    /// m_t <- beta1 * m_{t-1} + (1 - beta1) * g
+    /// v_t <- beta2 * v_{t-1} + (1 - beta2) * g * g
+    /// variable <- variable - lr_t * m_t / (sqrt(v_t) + epsilon)
+    /// ```
+    ///
+    /// ### Args:
+    ///   * learning_rate: A scalar `Tensor`. The learning rate (usually 0.001).
+    ///   * beta1: A scalar `Tensor`. The exponential decay rate for the 1st moment
+    ///     estimates (usually 0.9).
+    ///   * beta2: A scalar `Tensor`. The exponential decay rate for the 2nd moment
+    ///     estimates (usually 0.999).
+    ///   * epsilon: A scalar `Tensor`. A small constant for numerical stability
+    ///     (usually 1e-08).
+    ///   * use_locking: If `true` use locks for update operations.
+    ///   * name: Name prefix for the operations created when applying gradients.
+    ///     Defaults to "Adam" if empty.
+    pub fn new<Tx, Tb1, Tb2, Te>(
+        learning_rate: Tx,
+        beta1: Tb1,
+        beta2: Tb2,
+        epsilon: Te,
+        use_locking: bool,
+        name: &str,
+    ) -> AdamOptimizer
+    where
+        Tx: Into<Tensor>,
+        Tb1: Into<Tensor>,
+        Tb2: Into<Tensor>,
+        Te: Into<Tensor>,
+    {
+        AdamOptimizer {
+            learning_rate: learning_rate.into(),
+            beta1: beta1.into(),
+            beta2: beta2.into(),
+            epsilon: epsilon.into(),
+            use_locking,
+            name: default_name(name, "Adam"),
+            m: HashMap::new(),
+            v: HashMap::new(),
+            beta1_power: None,
+            beta2_power: None,
+        }
+    }
+
+    /// Returns the slot named "m" or "v" created for `var`, if any.
+    pub fn get_slot(&self, var: &Tensor, name: &str) -> Option<&Variable> {
+        match name {
+            "m" => self.m.get(var),
+            "v" => self.v.get(var),
+            _ => None,
+        }
+    }
+}
+
+impl Optimizer for AdamOptimizer {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn create_slots(&mut self, scope: &mut Scope, var_list: &[Tensor]) -> Result<()> {
+        if self.beta1_power.is_none() {
+            let scope = &mut scope.variable_scope("", Some(self.name.as_str()), None)?;
+            let beta1_power = scope.get_variable_with_initializer(self.beta1, false, "beta1_power")?;
+            let beta2_power = scope.get_variable_with_initializer(self.beta2, false, "beta2_power")?;
+            scope.set_not_trainable(&beta1_power);
+            scope.set_not_trainable(&beta2_power);
+            self.beta1_power = Some(beta1_power);
+            self.beta2_power = Some(beta2_power);
+        }
+        for var in var_list {
+            if !self.m.contains_key(var) {
+                let m = create_zeros_slot(scope, *var, "m", true)?;
+                self.m.insert(*var, m);
+                let v = create_zeros_slot(scope, *var, "v", true)?;
+                self.v.insert(*var, v);
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_dense(&mut self, scope: &mut Scope, grad: Tensor, var: Tensor) -> Result<Tensor> {
+        let (m, v) = (self.m[&var], self.v[&var]);
+        let beta1_power = cast_like(scope, self.beta1_power.unwrap().into(), &var)?;
+        let beta2_power = cast_like(scope, self.beta2_power.unwrap().into(), &var)?;
+        let lr = cast_like(scope, self.learning_rate, &var)?;
+        let beta1 = cast_like(scope, self.beta1, &var)?;
+        let beta2 = cast_like(scope, self.beta2, &var)?;
+        let epsilon = cast_like(scope, self.epsilon, &var)?;
+        apply_adam(
+            scope,
+            var,
+            m.into(),
+            v.into(),
+            beta1_power,
+            beta2_power,
+            lr,
+            beta1,
+            beta2,
+            epsilon,
+            grad,
+            self.use_locking,
+            "",
+        )
+    }
+
+    fn finish(&mut self, scope: &mut Scope, mut update_ops: Vec<Tensor>) -> Result<Vec<Tensor>> {
+        // update the power accumulators after all the variables have been updated
+        let beta1_power = self.beta1_power.unwrap();
+        let beta2_power = self.beta2_power.unwrap();
+        let scope = &mut scope.try_control_dependencies(&update_ops)?;
+        let update_beta1 = {
+            let new_value = math_ops::multiply(scope, beta1_power, self.beta1, "")?;
+            state_ops::assign(scope, beta1_power, new_value, self.use_locking, "")?
+        };
+        let update_beta2 = {
+            let new_value = math_ops::multiply(scope, beta2_power, self.beta2, "")?;
+            state_ops::assign(scope, beta2_power, new_value, self.use_locking, "")?
+        };
+        update_ops.push(update_beta1);
+        update_ops.push(update_beta2);
+        Ok(update_ops)
+    }
+}
+
+fn default_name(name: &str, default: &str) -> String {
+    if name.is_empty() {
+        default.to_owned()
+    } else {
+        name.to_owned()
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn test_gradient_descent() {
+    let mut context = Scope::new();
+    let w = {
+        let init = context.constant(&[1_f32], &[] as &[i32], "").unwrap();
+        context.get_variable_with_initializer(init, false, "w").unwrap()
+    };
+    let loss = math_ops::multiply(&mut context, w, w, "").unwrap();
+    let lr = context.constant(&[0.1_f32], &[] as &[i32], "lr").unwrap();
+    let mut opt = GradientDescentOptimizer::new(lr, false, "");
+    let grads_and_vars = opt.compute_gradients(&mut context, loss, &[w.into()]).unwrap();
+    let grad = grads_and_vars[0].0.unwrap();
+    let op = opt.apply_dense(&mut context, grad, w.into()).unwrap();
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Float] == [0.8_f32]});
+}
+
+#[cfg(test)]
+fn assert_close(values: &[f32], expected: &[f32]) {
+    assert_eq!(values.len(), expected.len());
+    for (val, exp) in values.iter().zip(expected) {
+        assert!((val - exp).abs() < 1e-5, "{:?} != {:?}", values, expected);
+    }
+}
+
+/// Applies the constant gradient `[0.1, 0.1]` to a variable initialized to `[1., 2.]`
+/// for `steps` steps, and returns the final value of the variable and `fetch(var)`.
+#[cfg(test)]
+fn apply_constant_gradients<O, M, F>(make_opt: M, steps: usize, fetch: F) -> (Vec<f32>, Vec<f32>)
+where
+    O: Optimizer,
+    M: FnOnce(&mut Scope) -> O,
+    F: Fn(&O, Tensor) -> Tensor,
+{
+    use client::ClientSession;
+
+    let mut context = Scope::new();
+    let mut opt = make_opt(&mut context);
+    let var: Tensor = {
+        let init = context.constant(&[1_f32, 2.], &[2], "").unwrap();
+        context.get_variable_with_initializer(init, false, "var").unwrap().into()
+    };
+    let grad = context.constant(&[0.1_f32, 0.1], &[2], "grad").unwrap();
+    let train_op = opt.apply_gradients(&mut context, &[(Some(grad.into()), var)], None).unwrap();
+    let fetched = fetch(&opt, var);
+
+    let mut session = ClientSession::new(&mut context).unwrap();
+    for _ in 0..steps {
+        session.prune().target(vec![train_op]);
+        session.run(None).unwrap();
+    }
+    session.prune();
+    let var = session.fetch_as::<f32, _>(var).unwrap().to_vec();
+    let fetched = session.fetch_as::<f32, _>(fetched).unwrap().to_vec();
+    (var, fetched)
+}
+
+#[test]
+#[cfg(test)]
+fn test_minimize() {
+    use client::ClientSession;
+
+    let mut context = Scope::new();
+    let w = {
+        let init = context.constant(&[0_f32], &[] as &[i32], "").unwrap();
+        context.get_variable_with_initializer(init, false, "w").unwrap()
+    };
+    // loss = (w - 3)^2
+    let loss = {
+        let target = context.constant(&[3_f32], &[] as &[i32], "").unwrap();
+        let diff = math_ops::sub(&mut context, w, target, "").unwrap();
+        math_ops::multiply(&mut context, diff, diff, "").unwrap()
+    };
+    let global_step = context.get_or_create_global_step().unwrap();
+    let lr = context.constant(&[0.1_f32], &[] as &[i32], "lr").unwrap();
+    assert_eq!(context.trainable_variables(), vec![w]);
+    let mut opt = GradientDescentOptimizer::new(lr, false, "");
+    // with an empty var_list all the trainable variables are updated
+    let train_op = opt.minimize(&mut context, loss, &[], Some(global_step.into())).unwrap();
+    match *opt.minimize(&mut context, loss, &[], Some(w.into())).unwrap_err().kind() {
+        ErrorKind::InvalidArgument(_) => {}
+        ref err => panic!("unexpected error: {}", err),
+    }
+
+    let mut session = ClientSession::new(&mut context).unwrap();
+    let mut last_loss = 9_f32;
+    for _ in 0..10 {
+        session.prune().target(vec![train_op]);
+        session.run(None).unwrap();
+        session.prune();
+        let loss = session.fetch_as::<f32, _>(loss).unwrap()[0];
+        assert!(loss < last_loss);
+        last_loss = loss;
+    }
+    // w_t = 3 - 3 * 0.8^t
+    assert_close(&[last_loss], &[0.103_763]);
+    assert_eq!(session.fetch_as::<i64, _>(global_step).unwrap()[0], 10);
+}
+
+#[test]
+#[cfg(test)]
+fn test_momentum() {
+    let make_opt = |scope: &mut Scope| {
+        let lr = scope.constant(&[2_f32], &[] as &[i32], "lr").unwrap();
+        let momentum = scope.constant(&[0.9_f32], &[] as &[i32], "momentum").unwrap();
+        MomentumOptimizer::new(lr, momentum, false, false, "")
+    };
+    let (var, accum) = apply_constant_gradients(make_opt, 2, |opt, var| {
+        (*opt.get_slot(&var).unwrap()).into()
+    });
+    // accum = 0.9 * 0.1 + 0.1, var = var - 2 * 0.1 - 2 * accum
+    assert_close(&accum, &[0.19, 0.19]);
+    assert_close(&var, &[0.42, 1.42]);
+}
+
+#[test]
+#[cfg(test)]
+fn test_adam() {
+    let make_opt = |scope: &mut Scope| {
+        let lr = scope.constant(&[0.001_f32], &[] as &[i32], "lr").unwrap();
+        let beta1 = scope.constant(&[0.9_f32], &[] as &[i32], "beta1").unwrap();
+        let beta2 = scope.constant(&[0.999_f32], &[] as &[i32], "beta2").unwrap();
+        let epsilon = scope.constant(&[1e-8_f32], &[] as &[i32], "epsilon").unwrap();
+        AdamOptimizer::new(lr, beta1, beta2, epsilon, false, "")
+    };
+    let (var, m) = apply_constant_gradients(make_opt, 3, |opt, var| {
+        (*opt.get_slot(&var, "m").unwrap()).into()
+    });
+    // reference values computed with the update rule of the paper
+    assert_close(&m, &[0.0271, 0.0271]);
+    assert_close(&var, &[0.997, 1.997]);
+}
@@ -0,0 +1,401 @@
+//! Save and restore variables.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use super::*;
+use client::ClientSession;
+use framework::protobuf::ProtoWriter;
+use ops::{io_ops, state_ops};
+use TypedTensor;
+
+/// Name of the file which keeps track of the checkpoints saved in a directory.
+const CHECKPOINT_STATE_FILE: &str = "checkpoint";
+
+/// Saves and restores variables.
+///
+/// The constructor adds `save` and `restore` ops to the graph for the variables
+/// tracked in the scope, or for a given map of names to tensors. The variables are
+/// saved in [V2 checkpoint](https://www.tensorflow.org/extend/tool_developers/) files,
+/// which are compatible with the ones written and read by other TensorFlow clients.
+///
+/// ```ignore
+/// let saver = Saver::new(scope, "")?;
+/// let mut session = ClientSession::new(scope)?;
+/// // ... train ...
+/// let path = saver.save(&mut session, "/tmp/model/model.ckpt", Some(step))?;
+/// // ... later ...
+/// saver.restore(&mut session, &path)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Saver {
+    filename: Tensor,
+    save_op: Tensor,
+    restore_op: Tensor,
+    last_checkpoints: Vec<PathBuf>,
+}
+
+impl Saver {
+    /// Creates a saver for all the variables in the graph, saved with their own names.
+    ///
+    /// The ops are created under a name scope `name`, defaults to "save" if empty.
+    pub fn new(scope: &mut Scope, name: &str) -> Result<Saver> {
+        let var_list = scope
+            .global_variables()
+            .into_iter()
+            .map(|var| (var.get_name(scope), var.into()))
+            .collect();
+        Saver::with_var_list(scope, var_list, name)
+    }
+
+    /// Creates a saver for the given variables, the tensors are saved and restored
+    /// under the name they are mapped to.
+    ///
+    /// This can be used to restore a variable from a different one saved in a checkpoint,
+    /// for example the map returned by `ExponentialMovingAverage::variables_to_restore`
+    /// restores the variables from their moving averages.
+    pub fn with_var_list(
+        scope: &mut Scope,
+        var_list: HashMap<String, Tensor>,
+        name: &str,
+    ) -> Result<Saver> {
+        if var_list.is_empty() {
+            return Err(Error::from(ErrorKind::InvalidArgument(
+                "no variables to save".to_owned(),
+            )));
+        }
+        // sort by name so the graph is deterministic
+        let mut var_list: Vec<_> = var_list.into_iter().collect();
+        var_list.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let scope = &mut scope.try_name_scope(name, Some("save"))?;
+        let filename = scope.placeholder(DataType::String)?;
+        let names: Vec<String> = var_list.iter().map(|&(ref name, _)| name.clone()).collect();
+        let slices = vec![String::new(); var_list.len()];
+        let tensors = var_list.iter().map(|&(_, var)| var).collect();
+
+        let save_op = {
+            let save = io_ops::save_v2(scope, filename, &names, &slices, tensors, "")?;
+            let scope = &mut scope.try_control_dependencies(&[save])?;
+            scope.identity(filename, "control_dependency")?
+        };
+
+        let restore_op = {
+            let mut assign_ops = Vec::with_capacity(var_list.len());
+            for (name, var) in var_list {
+                let restored = io_ops::restore_v2(scope, filename, &name, "", var.dtype, "")?;
+                assign_ops.push(state_ops::assign(scope, var, restored, false, "")?);
+            }
+            let scope = &mut scope.try_control_dependencies(&assign_ops)?;
+            scope.identity(filename, "restore_all")?
+        };
+
+        Ok(Saver {
+            filename,
+            save_op,
+            restore_op,
+            last_checkpoints: vec![],
+        })
+    }
+
+    /// Saves the variables to a checkpoint with prefix `save_path`.
+    ///
+    /// If `global_step` is provided, it's appended to the prefix to create the
+    /// checkpoint filenames (e.g. "model.ckpt-1000"). A "checkpoint" file is written
+    /// in the same directory to keep track of the saved checkpoints, see
+    /// `latest_checkpoint`.
+    ///
+    /// The save op is run on its own, the feeds, fetches and targets set in `session`
+    /// are left untouched.
+    ///
+    /// Returns the prefix of the saved checkpoint, which can be passed to `restore`.
+    pub fn save<P: AsRef<Path>>(
+        &mut self,
+        session: &mut ClientSession,
+        save_path: P,
+        global_step: Option<i64>,
+    ) -> Result<PathBuf> {
+        let mut save_path = save_path.as_ref().to_owned();
+        if let Some(step) = global_step {
+            let file_name = match save_path.file_name() {
+                Some(name) => format!("{}-{}", name.to_string_lossy(), step),
+                None => {
+                    return Err(Error::from(ErrorKind::InvalidArgument(format!(
+                        "invalid checkpoint prefix `{}`",
+                        save_path.display()
+                    ))))
+                }
+            };
+            save_path.set_file_name(file_name);
+        }
+        let save_dir = save_path.parent().map(|p| p.to_owned()).unwrap_or_default();
+        if !save_dir.as_os_str().is_empty() {
+            fs::create_dir_all(&save_dir)?;
+        }
+
+        session
+            .make_callable(vec![self.filename], vec![self.save_op])?
+            .call(&[path_tensor(&save_path)?])?;
+
+        self.last_checkpoints.retain(|p| p != &save_path);
+        self.last_checkpoints.push(save_path.clone());
+        update_checkpoint_state(&save_dir, &self.last_checkpoints)?;
+        Ok(save_path)
+    }
+
+    /// Restores the variables from the checkpoint with prefix `save_path`.
+    ///
+    /// As with `save`, the feeds, fetches and targets set in `session` are left untouched.
+    pub fn restore<P: AsRef<Path>>(&self, session: &mut ClientSession, save_path: P) -> Result<()> {
+        session
+            .make_callable(vec![self.filename], vec![self.restore_op])?
+            .call(&[path_tensor(save_path.as_ref())?])?;
+        Ok(())
+    }
+
+    /// Returns the prefixes of the checkpoints saved by this saver, oldest first.
+    pub fn last_checkpoints(&self) -> &[PathBuf] {
+        &self.last_checkpoints
+    }
+
+    /// Returns the serialized `SaverDef` protocol buffer of this saver, which tells other
+    /// clients (e.g. the SavedModel loader) how to run the save and restore ops.
+    pub fn saver_def(&self, scope: &Scope) -> Vec<u8> {
+        // SaverDef {
+        //     string filename_tensor_name = 1;
+        //     string save_tensor_name = 2;
+        //     string restore_op_name = 3;
+        //     CheckpointFormatVersion version = 7;
+        // }
+        let mut saver_def = ProtoWriter::new();
+        saver_def
+            .string(1, &format!("{}:0", self.filename.get_name(scope)))
+            .string(2, &format!("{}:0", self.save_op.get_name(scope)))
+            .string(3, &self.restore_op.get_name(scope))
+            .varint(7, 2);
+        saver_def.into_bytes()
+    }
+}
+
+/// Returns the prefix of the most recently saved checkpoint in `checkpoint_dir`,
+/// if any, as recorded in the "checkpoint" file written by `Saver::save`.
+pub fn latest_checkpoint<P: AsRef<Path>>(checkpoint_dir: P) -> Option<PathBuf> {
+    let checkpoint_dir = checkpoint_dir.as_ref();
+    let mut content = String::new();
+    fs::File::open(checkpoint_dir.join(CHECKPOINT_STATE_FILE))
+        .and_then(|mut f| f.read_to_string(&mut content))
+        .ok()?;
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.starts_with("model_checkpoint_path:") {
+                unescape_text(line["model_checkpoint_path:".len()..].trim())
+            } else {
+                None
+            }
+        })
+        .next()
+        .map(|path| {
+            let path = PathBuf::from(path);
+            if path.is_absolute() {
+                path
+            } else {
+                checkpoint_dir.join(path)
+            }
+        })
+}
+
+/// Writes the checkpoint state file, in protobuf text format.
+fn update_checkpoint_state(save_dir: &Path, checkpoints: &[PathBuf]) -> Result<()> {
+    let relative = |path: &PathBuf| {
+        path.strip_prefix(save_dir).unwrap_or(path.as_path()).to_string_lossy().into_owned()
+    };
+    let mut content = String::new();
+    if let Some(last) = checkpoints.last() {
+        content.push_str(&format!("model_checkpoint_path: {}\n", escape_text(&relative(last))));
+    }
+    for path in checkpoints {
+        let path = escape_text(&relative(path));
+        content.push_str(&format!("all_model_checkpoint_paths: {}\n", path));
+    }
+    let mut file = fs::File::create(save_dir.join(CHECKPOINT_STATE_FILE))?;
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+/// Quotes a string as in protobuf text format, bytes which are not printable ASCII
+/// characters are escaped as octal sequences.
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for &byte in value.as_bytes() {
+        match byte {
+            b'\n' => escaped.push_str("\\n"),
+            b'\r' => escaped.push_str("\\r"),
+            b'\t' => escaped.push_str("\\t"),
+            b'"' => escaped.push_str("\\\""),
+            b'\'' => escaped.push_str("\\'"),
+            b'\\' => escaped.push_str("\\\\"),
+            0x20..=0x7e => escaped.push(byte as char),
+            _ => escaped.push_str(&format!("\\{:03o}", byte)),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// Parses a quoted string in protobuf text format, returns `None` if it's malformed.
+fn unescape_text(quoted: &str) -> Option<String> {
+    let quote = quoted.chars().next()?;
+    if quoted.len() < 2 || (quote != '"' && quote != '\'') || !quoted.ends_with(quote) {
+        return None;
+    }
+    let mut bytes = quoted[1..quoted.len() - 1].bytes();
+    let mut value = vec![];
+    while let Some(byte) = bytes.next() {
+        if byte != b'\\' {
+            value.push(byte);
+            continue;
+        }
+        let byte = match bytes.next()? {
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b't' => b'\t',
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b'f' => 0x0c,
+            b'v' => 0x0b,
+            digit @ b'0'..=b'7' => {
+                let mut code = u32::from(digit - b'0');
+                for _ in 0..2 {
+                    match bytes.clone().next() {
+                        Some(digit @ b'0'..=b'7') => {
+                            code = code * 8 + u32::from(digit - b'0');
+                            bytes.next();
+                        }
+                        _ => break,
+                    }
+                }
+                if code > 0xff {
+                    return None;
+                }
+                code as u8
+            }
+            b'x' => {
+                let mut code = 0_u8;
+                let mut digits = 0;
+                while digits < 2 {
+                    match bytes.clone().next().and_then(|c| (c as char).to_digit(16)) {
+                        Some(digit) => {
+                            code = code * 16 + digit as u8;
+                            bytes.next();
+                            digits += 1;
+                        }
+                        None => break,
+                    }
+                }
+                if digits == 0 {
+                    return None;
+                }
+                code
+            }
+            other => other,
+        };
+        value.push(byte);
+    }
+    String::from_utf8(value).ok()
+}
+
+fn path_tensor(path: &Path) -> Result<TensorContent> {
+    let path = path.to_str()
+        .ok_or_else(|| {
+            let msg = format!("checkpoint path `{}` is not valid unicode", path.display());
+            Error::from(ErrorKind::InvalidArgument(msg))
+        })?;
+    let mut tensor = TypedTensor::<String>::new(&[]);
+    tensor[0] = path.to_owned();
+    Ok(TensorContent::from(tensor))
+}
+
+#[test]
+#[cfg(test)]
+fn test_saver() {
+    use std::env;
+
+    let mut context = Scope::new();
+    let init = context.constant(&[1_i32, 2], &[2], "").unwrap();
+    let w = context.get_variable_with_initializer(init, true, "w").unwrap();
+    let change = {
+        let value = context.constant(&[3_i32, 4], &[2], "").unwrap();
+        state_ops::assign(&mut context, w, value, false, "").unwrap()
+    };
+    let mut saver = Saver::new(&mut context, "").unwrap();
+
+    let save_dir = env::temp_dir().join(format!("tf_rs_saver_{}", ::std::process::id()));
+    let mut session = ClientSession::new(&mut context).unwrap();
+    session.fetch(vec![w]);
+    let path = saver.save(&mut session, save_dir.join("model.ckpt"), Some(1)).unwrap();
+    assert_eq!(path, save_dir.join("model.ckpt-1"));
+    assert!(save_dir.join("model.ckpt-1.index").exists());
+    assert_eq!(latest_checkpoint(&save_dir), Some(path.clone()));
+    // the fetches set in the session are kept
+    match session.run(None).unwrap()[0] {
+        TensorContent::Int32(ref val) => assert_eq!(&val[..], &[1, 2]),
+        _ => panic!("wrong type"),
+    }
+
+    session.prune().target(vec![change]);
+    session.run(None).unwrap();
+    session.prune();
+    assert_eq!(&session.fetch_as::<i32, _>(w).unwrap()[..], &[3, 4]);
+
+    saver.restore(&mut session, &path).unwrap();
+    assert_eq!(&session.fetch_as::<i32, _>(w).unwrap()[..], &[1, 2]);
+    fs::remove_dir_all(&save_dir).unwrap();
+}
+
+#[test]
+#[cfg(test)]
+fn test_saver_remapped_names() {
+    use std::env;
+
+    let mut context = Scope::new();
+    let w = {
+        let init = context.constant(&[1_i32, 2], &[2], "").unwrap();
+        context.get_variable_with_initializer(init, true, "w").unwrap()
+    };
+    let v = {
+        let init = context.constant(&[0_i32, 0], &[2], "").unwrap();
+        context.get_variable_with_initializer(init, true, "v").unwrap()
+    };
+    // `w` is saved as "w/avg" and restored into `v`, as when restoring moving averages
+    let mut var_list = HashMap::new();
+    var_list.insert("w/avg".to_owned(), w.into());
+    let mut saver = Saver::with_var_list(&mut context, var_list, "").unwrap();
+    let mut var_list = HashMap::new();
+    var_list.insert("w/avg".to_owned(), v.into());
+    let restorer = Saver::with_var_list(&mut context, var_list, "").unwrap();
+
+    let save_dir = env::temp_dir().join(format!("tf_rs_saver_remapped_{}", ::std::process::id()));
+    let mut session = ClientSession::new(&mut context).unwrap();
+    let path = saver.save(&mut session, save_dir.join("model.ckpt"), None).unwrap();
+    assert_eq!(&session.fetch_as::<i32, _>(v).unwrap()[..], &[0, 0]);
+    restorer.restore(&mut session, &path).unwrap();
+    assert_eq!(&session.fetch_as::<i32, _>(v).unwrap()[..], &[1, 2]);
+    fs::remove_dir_all(&save_dir).unwrap();
+}
+
+#[test]
+#[cfg(test)]
+fn test_checkpoint_state_escaping() {
+    let path = "model \"\u{fc}\"\n.ckpt";
+    let escaped = escape_text(path);
+    assert_eq!(escaped, r#""model \"\303\274\"\n.ckpt""#);
+    assert_eq!(unescape_text(&escaped).unwrap(), path);
+    assert_eq!(unescape_text(r#"'a\x41\101\\'"#).unwrap(), "aAA\\");
+    assert!(unescape_text("\"model.ckpt").is_none());
+    assert!(unescape_text(r#""\777""#).is_none());
+}
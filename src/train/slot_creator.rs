@@ -55,7 +55,9 @@ where
     let validate_shape = val.get_shape(scope).is_fully_defined();
     let primary_op_name = format!("{}/{}", primary.get_name(scope), name.as_ref());
     let scope = &mut scope.variable_scope("", Some(primary_op_name.as_str()), None)?;
-    scope.get_variable_with_initializer(val, validate_shape, "")
+    let slot = scope.get_variable_with_initializer(val, validate_shape, "")?;
+    scope.set_not_trainable(&slot);
+    Ok(slot)
 }
 
 pub(crate) fn create_zeros_slot<S>(
@@ -67,9 +69,9 @@ pub(crate) fn create_zeros_slot<S>(
 where
     S: AsRef<str>,
 {
-    let scope = &mut scope.name_scope("zeros", None);
-    let slot_shape = array_ops::shape(scope, primary, None, "").unwrap();
-    let slot_shape_arr = slot_shape.get_shape(scope);
+    let scope = &mut scope.try_name_scope("zeros", None)?;
+    let slot_shape = array_ops::shape(scope, primary, None, "")?;
+    let slot_shape_arr = primary.get_shape(scope);
     if slot_shape_arr.is_fully_defined() {
         let initializer = init_ops::zeros_initializer(
             scope,
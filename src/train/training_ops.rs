@@ -0,0 +1,248 @@
+//! Ops which apply the updates computed by the optimizers to the variables.
+
+use std::path::{Path, PathBuf};
+
+use super::*;
+use ops::ControlFlow;
+
+/// All the inputs of the apply ops must have the same type as the variable.
+fn check_dtypes(inputs: &[Tensor]) -> Result<()> {
+    let var_dtype = inputs[0].dtype;
+    if let Some(x) = inputs.iter().find(|x| x.dtype != var_dtype) {
+        Err(Error::from(ErrorKind::DTypeMismatch(
+            "inputs of the apply op".to_owned(),
+            vec![var_dtype],
+            x.dtype,
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+///// ApplyGradientDescent /////
+
+/// Update `var` by subtracting `alpha` * `delta` from it.
+///
+/// ### Args
+/// * var: Should be from a Variable.
+/// * alpha: Scaling factor. Must be a scalar.
+/// * delta: The change.
+/// * use_locking: If `true`, the subtraction will be protected by a lock;
+///   otherwise the behavior is undefined, but may exhibit less contention.
+/// * name: A name for the operation (optional).
+///
+/// ### Returns
+/// * A mutable `Tensor`. Same as `var`.
+pub(crate) fn apply_gradient_descent<S>(
+    scope: &mut Scope,
+    var: Tensor,
+    alpha: Tensor,
+    delta: Tensor,
+    use_locking: bool,
+    name: S,
+) -> Result<Tensor>
+where
+    S: AsRef<Path>,
+{
+    scope.install(
+        ApplyGradientDescent::new(vec![var, alpha, delta], name)?.use_locking(&[use_locking]),
+    )
+}
+
+add_new_op!(ApplyGradientDescent,
+    constructor: [
+        fn new<S: AsRef<Path>>(inputs: Vec<Tensor>, name: S) -> Result<ApplyGradientDescent<'a>> {
+            check_dtypes(&inputs)?;
+            Ok(
+                ApplyGradientDescent {
+                    ident: NodeIdent::new(),
+                    elements: inputs,
+                    name: generate_name!(is_none: name),
+                    attributes: vec![],
+                    input_lists: vec![],
+                },
+            )
+        }
+    ],
+    digest: [DEFAULT_DIGEST: ApplyGradientDescent, INPUT0],
+    extra_funcs: [
+        fn use_locking(mut self, val: &'a [bool]) -> Self {
+            self.attributes.push(("use_locking", false, Attribute::Bool(val)));
+            self
+        }
+    ],
+    extra_attr: [],
+    output: [Tensor],
+);
+
+
+///// ApplyMomentum /////
+
+/// Update `var` according to the momentum scheme.
+///
+/// ```python
+/// accum = accum * momentum + grad
+/// var -= lr * accum
+/// ```
+///
+/// If `use_nesterov` is `true`, uses the Nesterov momentum instead:
+///
+/// ```python
+/// accum = accum * momentum + grad
+/// var -= grad * lr + accum * momentum * lr
+/// ```
+///
+/// ### Args
+/// * var: Should be from a Variable.
+/// * accum: Should be from a Variable.
+/// * lr: Scaling factor. Must be a scalar.
+/// * grad: The gradient.
+/// * momentum: Momentum. Must be a scalar.
+/// * use_locking: If `true`, updating of the var and accum tensors will be protected
+///   by a lock; otherwise the behavior is undefined, but may exhibit less contention.
+/// * use_nesterov: If `true`, the tensor passed to compute grad will be
+///   `var - lr * momentum * accum`, so in the end, the var you get is actually
+///   `var - lr * momentum * accum`.
+/// * name: A name for the operation (optional).
+///
+/// ### Returns
+/// * A mutable `Tensor`. Same as `var`.
+pub(crate) fn apply_momentum<S>(
+    scope: &mut Scope,
+    var: Tensor,
+    accum: Tensor,
+    lr: Tensor,
+    grad: Tensor,
+    momentum: Tensor,
+    use_locking: bool,
+    use_nesterov: bool,
+    name: S,
+) -> Result<Tensor>
+where
+    S: AsRef<Path>,
+{
+    scope.install(
+        ApplyMomentum::new(vec![var, accum, lr, grad, momentum], name)?
+            .use_locking(&[use_locking])
+            .use_nesterov(&[use_nesterov]),
+    )
+}
+
+add_new_op!(ApplyMomentum,
+    constructor: [
+        fn new<S: AsRef<Path>>(inputs: Vec<Tensor>, name: S) -> Result<ApplyMomentum<'a>> {
+            check_dtypes(&inputs)?;
+            Ok(
+                ApplyMomentum {
+                    ident: NodeIdent::new(),
+                    elements: inputs,
+                    name: generate_name!(is_none: name),
+                    attributes: vec![],
+                    input_lists: vec![],
+                },
+            )
+        }
+    ],
+    digest: [DEFAULT_DIGEST: ApplyMomentum, INPUT0],
+    extra_funcs: [
+        fn use_locking(mut self, val: &'a [bool]) -> Self {
+            self.attributes.push(("use_locking", false, Attribute::Bool(val)));
+            self
+        }
+
+        fn use_nesterov(mut self, val: &'a [bool]) -> Self {
+            self.attributes.push(("use_nesterov", false, Attribute::Bool(val)));
+            self
+        }
+    ],
+    extra_attr: [],
+    output: [Tensor],
+);
+
+
+///// ApplyAdam /////
+
+/// Update `var` according to the Adam algorithm.
+///
+/// ```python
+/// lr_t = learning_rate * sqrt(1 - beta2^t) / (1 - beta1^t)
+/// m_t = beta1 * m_{t-1} + (1 - beta1) * g_t
+/// v_t = beta2 * v_{t-1} + (1 - beta2) * g_t * g_t
+/// variable = variable - lr_t * m_t / (sqrt(v_t) + epsilon)
+/// ```
+///
+/// ### Args
+/// * var: Should be from a Variable.
+/// * m: Should be from a Variable.
+/// * v: Should be from a Variable.
+/// * beta1_power: Must be a scalar.
+/// * beta2_power: Must be a scalar.
+/// * lr: Scaling factor. Must be a scalar.
+/// * beta1: Momentum factor. Must be a scalar.
+/// * beta2: Momentum factor. Must be a scalar.
+/// * epsilon: Ridge term. Must be a scalar.
+/// * grad: The gradient.
+/// * use_locking: If `true`, updating of the var, m, and v tensors will be protected
+///   by a lock; otherwise the behavior is undefined, but may exhibit less contention.
+/// * name: A name for the operation (optional).
+///
+/// ### Returns
+/// * A mutable `Tensor`. Same as `var`.
+pub(crate) fn apply_adam<S>(
+    scope: &mut Scope,
+    var: Tensor,
+    m: Tensor,
+    v: Tensor,
+    beta1_power: Tensor,
+    beta2_power: Tensor,
+    lr: Tensor,
+    beta1: Tensor,
+    beta2: Tensor,
+    epsilon: Tensor,
+    grad: Tensor,
+    use_locking: bool,
+    name: S,
+) -> Result<Tensor>
+where
+    S: AsRef<Path>,
+{
+    let inputs = vec![
+        var,
+        m,
+        v,
+        beta1_power,
+        beta2_power,
+        lr,
+        beta1,
+        beta2,
+        epsilon,
+        grad,
+    ];
+    scope.install(ApplyAdam::new(inputs, name)?.use_locking(&[use_locking]))
+}
+
+add_new_op!(ApplyAdam,
+    constructor: [
+        fn new<S: AsRef<Path>>(inputs: Vec<Tensor>, name: S) -> Result<ApplyAdam<'a>> {
+            check_dtypes(&inputs)?;
+            Ok(
+                ApplyAdam {
+                    ident: NodeIdent::new(),
+                    elements: inputs,
+                    name: generate_name!(is_none: name),
+                    attributes: vec![],
+                    input_lists: vec![],
+                },
+            )
+        }
+    ],
+    digest: [DEFAULT_DIGEST: ApplyAdam, INPUT0],
+    extra_funcs: [
+        fn use_locking(mut self, val: &'a [bool]) -> Self {
+            self.attributes.push(("use_locking", false, Attribute::Bool(val)));
+            self
+        }
+    ],
+    extra_attr: [],
+    output: [Tensor],
+);
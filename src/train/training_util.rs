@@ -0,0 +1,39 @@
+//! Utility functions for training.
+
+use super::*;
+use ops::state_ops;
+
+/// Returns an op which increments the global step variable by `increment`.
+///
+/// The global step is created if it doesn't exist yet. The returned op can be
+/// fetched directly or used as a control dependency, e.g. to only increment the
+/// step after the training ops have been run:
+///
+/// ```ignore
+/// let step = {
+///     let scope = &mut scope.control_dependencies(&[train_op]);
+///     increment_global_step(scope, 1)?
+/// };
+/// ```
+pub fn increment_global_step(scope: &mut Scope, increment: i64) -> Result<Tensor> {
+    let global_step = scope.get_or_create_global_step()?;
+    let increment = scope.constant(&[increment], &[] as &[i32], "")?;
+    state_ops::assign_add(scope, global_step, increment, false, "")
+}
+
+#[test]
+#[cfg(test)]
+fn test_global_step() {
+    let mut context = Scope::new();
+    let step = {
+        let scope = &mut context.name_scope("train", None);
+        let step = scope.get_or_create_global_step().unwrap();
+        assert_eq!(step.get_name(scope), "global_step");
+        step
+    };
+    assert_eq!(context.get_or_create_global_step().unwrap(), step);
+
+    let op = increment_global_step(&mut context, 2).unwrap();
+    let results = test_suite!(run_op: [op]; context, input: {});
+    test_suite!(results; assert: {[0;Int64] == [2_i64]});
+}
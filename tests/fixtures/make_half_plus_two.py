@@ -0,0 +1,107 @@
+#!/usr/bin/env python3
+"""Writes the `half_plus_two` SavedModel fixture used by the loader tests.
+
+The model computes `y = 0.5 * x + 2` for a float placeholder `x`, it has no
+variables and a single "serving_default" signature, tagged with "serve".
+
+Only the standard library is used, the protocol buffers are encoded by hand.
+"""
+
+import os
+import struct
+
+DT_FLOAT = 1
+
+
+def varint(n):
+    out = bytearray()
+    while True:
+        b = n & 0x7F
+        n >>= 7
+        if n:
+            out.append(b | 0x80)
+        else:
+            out.append(b)
+            return bytes(out)
+
+
+def key(field, wire_type):
+    return varint((field << 3) | wire_type)
+
+
+def int_field(field, n):
+    return key(field, 0) + varint(n)
+
+
+def bytes_field(field, data):
+    if isinstance(data, str):
+        data = data.encode()
+    return key(field, 2) + varint(len(data)) + data
+
+
+def map_entry(field, k, v):
+    return bytes_field(field, bytes_field(1, k) + bytes_field(2, v))
+
+
+def attr_type(dtype):
+    return int_field(6, dtype)
+
+
+def attr_unknown_shape():
+    return bytes_field(7, int_field(3, 1))
+
+
+def attr_scalar_float(value):
+    floats = struct.pack("<f", value)
+    tensor = int_field(1, DT_FLOAT) + bytes_field(2, b"") + bytes_field(5, floats)
+    return bytes_field(8, tensor)
+
+
+def node(name, op, inputs=(), attrs=()):
+    out = bytes_field(1, name) + bytes_field(2, op)
+    for i in inputs:
+        out += bytes_field(3, i)
+    for k, v in attrs:
+        out += map_entry(5, k, v)
+    return out
+
+
+def tensor_info(name):
+    return bytes_field(1, name) + int_field(2, DT_FLOAT) + bytes_field(3, int_field(3, 1))
+
+
+def main():
+    graph_def = b"".join(
+        bytes_field(1, n)
+        for n in [
+            node("x", "Placeholder", attrs=[("dtype", attr_type(DT_FLOAT)),
+                                            ("shape", attr_unknown_shape())]),
+            node("a", "Const", attrs=[("dtype", attr_type(DT_FLOAT)),
+                                      ("value", attr_scalar_float(0.5))]),
+            node("b", "Const", attrs=[("dtype", attr_type(DT_FLOAT)),
+                                      ("value", attr_scalar_float(2.0))]),
+            node("mul", "Mul", ["a", "x"], [("T", attr_type(DT_FLOAT))]),
+            node("y", "Add", ["mul", "b"], [("T", attr_type(DT_FLOAT))]),
+        ]
+    ) + bytes_field(4, int_field(1, 24))
+
+    signature = (
+        map_entry(1, "x", tensor_info("x:0"))
+        + map_entry(2, "y", tensor_info("y:0"))
+        + bytes_field(3, "tensorflow/serving/predict")
+    )
+    meta_graph = (
+        bytes_field(1, bytes_field(4, "serve"))
+        + bytes_field(2, graph_def)
+        + map_entry(5, "serving_default", signature)
+    )
+    saved_model = int_field(1, 1) + bytes_field(2, meta_graph)
+
+    out_dir = os.path.join(os.path.dirname(os.path.abspath(__file__)), "half_plus_two")
+    os.makedirs(out_dir, exist_ok=True)
+    with open(os.path.join(out_dir, "saved_model.pb"), "wb") as f:
+        f.write(saved_model)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,31 @@
+#!/usr/bin/env python3
+"""Writes the `.npy` and `.npz` fixtures used by the NumPy serialization tests.
+
+* `floats.npy`: a (2, 3) float32 array, `np.arange(6) / 2`.
+* `arrays.npz`: written by `np.savez`, which stores each entry with
+  `force_zip64=True`, so the local headers carry zip64 extra fields:
+  - `x`: the int64 array `[1, 2, 3]`,
+  - `y`: the float64 scalar `1.5`,
+  - `s`: the unicode array `["ab", "cde"]` (`<U3`).
+"""
+
+import os
+
+import numpy as np
+
+
+def main():
+    out_dir = os.path.join(os.path.dirname(os.path.abspath(__file__)), "npy")
+    os.makedirs(out_dir, exist_ok=True)
+    floats = (np.arange(6) / 2).astype(np.float32).reshape(2, 3)
+    np.save(os.path.join(out_dir, "floats.npy"), floats)
+    np.savez(
+        os.path.join(out_dir, "arrays.npz"),
+        x=np.array([1, 2, 3], dtype=np.int64),
+        y=np.float64(1.5),
+        s=np.array(["ab", "cde"]),
+    )
+
+
+if __name__ == "__main__":
+    main()